@@ -0,0 +1,454 @@
+use crate::{RResult, evaluator, lexer, lexer::Token, script};
+use std::{fs, path::Path};
+
+/// A document of a type collected from scripts.
+#[derive(Default)]
+struct TypeDoc {
+    name: String,
+    /// Whether the type is defined by `|>`, `|+` or `|&` in the scripts.
+    defined: bool,
+    doc: Option<String>,
+    fields: Vec<FieldDoc>,
+    variants: Vec<VariantDoc>,
+    /// Verbs required by the interface defined by `|&`.
+    requirements: Vec<RequirementDoc>,
+    /// Names of interfaces declared by `&>`.
+    interfaces: Vec<String>,
+    verbs: Vec<VerbDoc>,
+}
+
+struct RequirementDoc {
+    name: String,
+    types: String,
+    doc: Option<String>,
+}
+
+struct VariantDoc {
+    name: String,
+    fields: Vec<FieldDoc>,
+    doc: Option<String>,
+}
+
+struct FieldDoc {
+    private: bool,
+    name: String,
+    ty: String,
+    doc: Option<String>,
+}
+
+struct VerbDoc {
+    private: bool,
+    mutable: bool,
+    name: String,
+    types: String,
+    doc: Option<String>,
+}
+
+/// A function to run the `doc` subcommand.
+///
+/// * `doc <path>...` - outputs Markdown documents of types and functions defined in scripts.
+///   A directory is searched recursively for `.fuz` files.
+/// * `doc --builtins <dir>` - writes documents of builtin types into `dir`.
+pub fn run(args: Vec<String>) -> RResult<()> {
+    match args.first().map(|n| n.as_str()) {
+        Some("--builtins") => {
+            let Some(dir) = args.get(1) else {
+                return Err("error: no directory passed after --builtins.".into());
+            };
+            for (file, content) in evaluator::builtin_pages() {
+                let path = Path::new(dir).join(file);
+                fs::write(&path, content).map_err(|e| format!("error: {}: {e}", path.display()))?;
+            }
+            Ok(())
+        }
+        Some(_) => {
+            let mut paths = Vec::new();
+            for n in args.iter() {
+                collect_scripts(Path::new(n), &mut paths)?;
+            }
+            let mut docs = Vec::new();
+            for n in paths {
+                let content =
+                    fs::read_to_string(&n).map_err(|e| format!("error: {}: {e}", n.display()))?;
+                let tokens = lexer::lex(script::strip_shebang(&content))?;
+                scan(&tokens, &mut docs);
+            }
+            print!("{}", render(&docs));
+            Ok(())
+        }
+        None => Err("error: no path passed to doc.".into()),
+    }
+}
+
+fn collect_scripts(path: &Path, paths: &mut Vec<std::path::PathBuf>) -> RResult<()> {
+    if !path.is_dir() {
+        paths.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(path)
+        .map_err(|e| format!("error: {}: {e}", path.display()))?
+        .filter_map(|n| n.ok().map(|n| n.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    for n in entries {
+        if n.is_dir() {
+            collect_scripts(&n, paths)?;
+        } else if n.extension().is_some_and(|n| n == "fuz") {
+            paths.push(n);
+        }
+    }
+    Ok(())
+}
+
+/// A function to collect documents of top-level definitions.
+///
+/// Definitions are found without evaluation, so only the following literal forms are recognized:
+///
+/// * `[...] |> 'typename`
+/// * `[...] |+ 'typename`
+/// * `[...] |& 'interfacename`
+/// * `'typename &> 'interfacename`
+/// * `{...} : [...] -> 'typename:functionname` (also with `=>` or `::`)
+fn scan(tokens: &[Token], docs: &mut Vec<TypeDoc>) {
+    let mut depth = 0;
+    let mut doc = None;
+    for (i, n) in tokens.iter().enumerate() {
+        match n {
+            Token::LBrace | Token::LBracket | Token::LHashBracket | Token::LParen => depth += 1,
+            Token::RBrace | Token::RBracket | Token::RParen => depth -= 1,
+            Token::Doc(n) if depth == 0 => doc = Some(n.clone()),
+            Token::Dot if depth == 0 => doc = None,
+            Token::Label(vn) if depth == 0 => {
+                let Some(Token::Symbol(trg)) = tokens.get(i + 1) else {
+                    continue;
+                };
+                match vn.as_str() {
+                    "|>" => scan_user_type(&tokens[..i], trg, doc.take(), docs),
+                    "|+" => scan_sum_type(&tokens[..i], trg, doc.take(), docs),
+                    "|&" => scan_interface(&tokens[..i], trg, doc.take(), docs),
+                    "&>" => {
+                        if let Some(Token::Symbol(ty)) = i.checked_sub(1).map(|n| &tokens[n]) {
+                            get_or_insert(docs, ty).interfaces.push(trg.clone());
+                        }
+                    }
+                    "->" | "=>" => scan_function(&tokens[..i], vn == "->", trg, doc.take(), docs),
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn scan_user_type(tokens: &[Token], name: &str, doc: Option<String>, docs: &mut Vec<TypeDoc>) {
+    let Some(fields) = last_bracket(tokens) else {
+        return;
+    };
+
+    let t = get_or_insert(docs, name);
+    t.defined = true;
+    t.doc = doc;
+    t.fields = scan_fields(fields);
+}
+
+fn scan_sum_type(tokens: &[Token], name: &str, doc: Option<String>, docs: &mut Vec<TypeDoc>) {
+    let Some(variants) = last_bracket(tokens) else {
+        return;
+    };
+
+    let mut elements = elements(variants).into_iter().peekable();
+    let mut v = Vec::new();
+    while let Some(n) = elements.next() {
+        let [Token::Symbol(n)] = n else {
+            continue;
+        };
+        let fields = match elements.next_if(|n| matches!(n, [Token::LBracket, ..])) {
+            Some([_, inner @ .., _]) => scan_fields(inner),
+            _ => Vec::new(),
+        };
+        let doc = match elements.next_if(|n| matches!(n, [Token::String(_)])) {
+            Some([Token::String(n)]) => Some(n.clone()),
+            _ => None,
+        };
+        v.push(VariantDoc {
+            name: n.clone(),
+            fields,
+            doc,
+        });
+    }
+
+    let t = get_or_insert(docs, name);
+    t.defined = true;
+    t.doc = doc;
+    t.variants = v;
+}
+
+fn scan_interface(tokens: &[Token], name: &str, doc: Option<String>, docs: &mut Vec<TypeDoc>) {
+    let Some(verbs) = last_bracket(tokens) else {
+        return;
+    };
+
+    let mut elements = elements(verbs).into_iter().peekable();
+    let mut v = Vec::new();
+    while let Some(n) = elements.next() {
+        let [Token::Symbol(n)] = n else {
+            continue;
+        };
+        let types = match elements.next_if(|n| matches!(n, [Token::LBracket, ..])) {
+            Some(n) => format_type(n),
+            None => continue,
+        };
+        let doc = match elements.next_if(|n| matches!(n, [Token::String(_)])) {
+            Some([Token::String(n)]) => Some(n.clone()),
+            _ => None,
+        };
+        v.push(RequirementDoc {
+            name: n.clone(),
+            types,
+            doc,
+        });
+    }
+
+    let t = get_or_insert(docs, name);
+    t.defined = true;
+    t.doc = doc;
+    t.requirements = v;
+}
+
+/// A function to collect field definitions written in the same way as `|>`.
+fn scan_fields(tokens: &[Token]) -> Vec<FieldDoc> {
+    let mut elements = elements(tokens).into_iter();
+    let mut v = Vec::new();
+    let mut doc = None;
+    while let Some(n) = elements.next() {
+        let n = match n {
+            [Token::Doc(n)] => {
+                doc = Some(n.clone());
+                continue;
+            }
+            [Token::Symbol(n)] => n,
+            _ => continue,
+        };
+        let (private, name) = if let Some(n) = n.strip_prefix("::") {
+            (true, n)
+        } else if let Some(n) = n.strip_prefix(':') {
+            (false, n)
+        } else {
+            continue;
+        };
+        let Some(ty) = elements.next() else {
+            break;
+        };
+        v.push(FieldDoc {
+            private,
+            name: name.to_string(),
+            ty: format_type(ty),
+            doc: doc.take(),
+        });
+    }
+    v
+}
+
+fn scan_function(
+    tokens: &[Token],
+    mutable: bool,
+    trg: &str,
+    doc: Option<String>,
+    docs: &mut Vec<TypeDoc>,
+) {
+    let (private, ty, name) = if let Some((n, m)) = trg.split_once("::") {
+        (true, n, m)
+    } else if let Some((n, m)) = trg.split_once(':') {
+        (false, n, m)
+    } else {
+        return;
+    };
+
+    let mut tokens = tokens;
+    while let [n @ .., Token::Comma | Token::Semicolon] = tokens {
+        tokens = n;
+    }
+    let Some(types) = last_bracket(tokens) else {
+        return;
+    };
+    let n = tokens.len() - types.len() - 2;
+    if !matches!(n.checked_sub(1).map(|n| &tokens[n]), Some(Token::Label(n)) if n == ":") {
+        return;
+    }
+
+    let types = elements(types)
+        .into_iter()
+        .map(format_type)
+        .collect::<Vec<_>>()
+        .join(" ");
+    get_or_insert(docs, ty).verbs.push(VerbDoc {
+        private,
+        mutable,
+        name: name.to_string(),
+        types,
+        doc,
+    });
+}
+
+fn get_or_insert<'a>(docs: &'a mut Vec<TypeDoc>, name: &str) -> &'a mut TypeDoc {
+    if let Some(i) = docs.iter().position(|n| n.name == name) {
+        return &mut docs[i];
+    }
+    docs.push(TypeDoc {
+        name: name.to_string(),
+        ..Default::default()
+    });
+    docs.last_mut().unwrap()
+}
+
+/// A function to get tokens inside the array block at the end of `tokens`.
+fn last_bracket(tokens: &[Token]) -> Option<&[Token]> {
+    let [inner @ .., Token::RBracket] = tokens else {
+        return None;
+    };
+    let mut depth = 0;
+    for (i, n) in inner.iter().enumerate().rev() {
+        match n {
+            Token::RBracket => depth += 1,
+            Token::LBracket if depth == 0 => return Some(&inner[i + 1..]),
+            Token::LHashBracket if depth == 0 => return None,
+            Token::LBracket | Token::LHashBracket => depth -= 1,
+            _ => (),
+        }
+    }
+    None
+}
+
+/// A function to split tokens in an array block into elements, skipping separators.
+///
+/// An array, map or lazy block is an element.
+fn elements(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut v = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, n) in tokens.iter().enumerate() {
+        match n {
+            Token::Dot | Token::Comma | Token::Semicolon if depth == 0 => start = i + 1,
+            Token::LBracket | Token::LHashBracket | Token::LBrace => {
+                if depth == 0 {
+                    start = i;
+                }
+                depth += 1;
+            }
+            Token::RBracket | Token::RBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    v.push(&tokens[start..=i]);
+                }
+            }
+            _ if depth == 0 => v.push(&tokens[i..=i]),
+            _ => (),
+        }
+    }
+    v
+}
+
+/// A function to format a type element as it's written in type lists.
+fn format_type(tokens: &[Token]) -> String {
+    match tokens {
+        [Token::Symbol(n)] => format!("'{n}"),
+        [Token::LBracket, inner @ .., Token::RBracket] => {
+            let v = elements(inner)
+                .into_iter()
+                .map(format_type)
+                .collect::<Vec<_>>();
+            format!("[{}]", v.join(" "))
+        }
+        n => n
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn render(docs: &[TypeDoc]) -> String {
+    let mut pages = Vec::new();
+    for t in docs {
+        let mut s = format!("# {}\n", t.name);
+        if t.defined {
+            s.push_str("\n## General\n");
+            if let Some(n) = &t.doc {
+                s.push_str(&format!("\n{n}\n"));
+            }
+            if !t.fields.is_empty() {
+                s.push_str(
+                    "\n| Field | Type | Visibility | Description |\n| --- | --- | --- | --- |\n",
+                );
+                for n in t.fields.iter() {
+                    s.push_str(&format!(
+                        "| `{}` | `{}` | {} | {} |\n",
+                        n.name,
+                        n.ty,
+                        visibility(n.private),
+                        n.doc.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+            if !t.variants.is_empty() {
+                s.push_str(
+                    "\n| Variant | Field | Type | Visibility | Description |\n\
+                     | --- | --- | --- | --- | --- |\n",
+                );
+                for v in t.variants.iter() {
+                    s.push_str(&format!(
+                        "| `{}` | | | | {} |\n",
+                        v.name,
+                        v.doc.as_deref().unwrap_or_default()
+                    ));
+                    for n in v.fields.iter() {
+                        s.push_str(&format!(
+                            "| | `{}` | `{}` | {} | {} |\n",
+                            n.name,
+                            n.ty,
+                            visibility(n.private),
+                            n.doc.as_deref().unwrap_or_default()
+                        ));
+                    }
+                }
+            }
+            if !t.requirements.is_empty() {
+                s.push_str("\n| Verb | Types | Description |\n| --- | --- | --- |\n");
+                for n in t.requirements.iter() {
+                    s.push_str(&format!(
+                        "| `{}` | `@{}` | {} |\n",
+                        n.name,
+                        n.types,
+                        n.doc.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        if !t.interfaces.is_empty() {
+            s.push_str("\n## Interfaces\n\n");
+            for n in t.interfaces.iter() {
+                s.push_str(&format!("- `{n}`\n"));
+            }
+        }
+        if !t.verbs.is_empty() {
+            s.push_str("\n## Functions\n");
+        }
+        for n in t.verbs.iter() {
+            s.push_str(&format!("\n### `{}`: `@[{}]`\n", n.name, n.types));
+            if let Some(n) = &n.doc {
+                s.push_str(&format!("\n{n}\n"));
+            }
+            s.push_str(&format!(
+                "\n- Visibility: {}\n- Mutability: {}\n",
+                visibility(n.private),
+                if n.mutable { "mutable" } else { "immutable" }
+            ));
+        }
+        pages.push(s);
+    }
+    pages.join("\n")
+}
+
+fn visibility(private: bool) -> &'static str {
+    if private { "private" } else { "public" }
+}
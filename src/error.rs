@@ -0,0 +1,43 @@
+use std::{
+    error::Error,
+    fmt::{Display, Result},
+};
+
+/// An error found while splitting source code into tokens.
+#[derive(Debug)]
+pub struct LexError(pub String);
+
+impl Display for LexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for LexError {}
+
+/// An error found while checking the types of the arguments passed to a verb.
+#[derive(Debug)]
+pub struct TypeError(pub String);
+
+impl Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for TypeError {}
+
+/// A request to terminate the process with the exit code.
+///
+/// NOTE: This is propagated as an error so that all scopes are cleaned up
+///       on the way to the top level.
+#[derive(Debug)]
+pub struct Exit(pub i32);
+
+impl Display for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        write!(f, "exit with {}.", self.0)
+    }
+}
+
+impl Error for Exit {}
@@ -5,6 +5,8 @@ mod usertype;
 mod value;
 mod variable;
 
+pub use functions::builtin_pages;
+
 use crate::{lexer::*, *};
 use std::collections::HashMap;
 
@@ -12,6 +14,37 @@ use std::collections::HashMap;
 pub struct EnterLazyParams {
     pub slf: Option<value::Value>,
     pub args: Option<Vec<value::Value>>,
+    /// Immutable variables defined in the new scope, such as fields bound by a match arm.
+    pub vars: Vec<(String, value::Value)>,
+    /// Variable frames captured by the lazy block or the function,
+    /// which are visible in the new scope instead of the current ones.
+    pub captures: Option<variable::Captures>,
+}
+
+/// A request to return the value from the current function, made by `?^`.
+///
+/// NOTE: This is propagated as an error like `error::Exit`,
+///       and caught where a function is called.
+#[derive(Debug)]
+pub struct Propagation(pub value::Value);
+
+impl std::fmt::Display for Propagation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error: {} propagated out of functions.", self.0)
+    }
+}
+
+impl std::error::Error for Propagation {}
+
+/// A function to take the value of `Propagation` as the result of a function.
+fn catch_propagation(result: RResult<value::Value>) -> RResult<value::Value> {
+    match result {
+        Err(e) => match e.downcast::<Propagation>() {
+            Ok(n) => Ok(n.0),
+            Err(e) => Err(e),
+        },
+        n => n,
+    }
 }
 
 #[derive(Default)]
@@ -20,6 +53,10 @@ pub struct Environment {
     vr_map: variable::VariableMapStack,
     ut_map: usertype::UserTypeMapStack,
     args: Vec<Vec<value::Value>>,
+    doc: Option<String>,
+    /// Doc comments before field symbols in the arrays evaluated in the current sentence,
+    /// as pairs of a field symbol and its document in the order of appearance.
+    field_docs: Vec<(String, String)>,
 }
 
 impl Environment {
@@ -33,6 +70,9 @@ impl Environment {
         if let Some(n) = params.args {
             self.args.push(n);
         }
+        for (k, v) in params.vars {
+            self.vr_map.insert_local(k, v);
+        }
     }
 
     pub fn cleanup_block_scope(&mut self, pop_args: bool) {
@@ -54,7 +94,58 @@ impl Environment {
     }
 
     fn get_self_type(&self) -> Option<types::TypeId> {
-        self.vr_map.get("##").map(|n| n.typeid())
+        self.vr_map.get_type("##")
+    }
+
+    /// A function to get the document of a user type, function or variable.
+    ///
+    /// * `name` - `typename`, `typename:functionname` or `variablename`.
+    pub fn get_doc(&self, name: &str) -> Option<String> {
+        if let Some(ut) = self.ut_map.get(name) {
+            let mut s = ut.doc.clone().unwrap_or_default();
+            push_field_docs(&mut s, &ut.fields, "");
+            for n in ut.variants.iter() {
+                s.push_str(&format!("\n{}", n.name));
+                if let Some(d) = &n.doc {
+                    s.push_str(&format!(" -- {d}"));
+                }
+                push_field_docs(&mut s, &n.fields, "  ");
+            }
+            return Some(s.trim_start().to_string());
+        }
+        if let Some(n) = self.ut_map.get_interface(name) {
+            let mut s = n.doc.clone().unwrap_or_default();
+            for v in n.verbs.iter() {
+                s.push_str(&format!(
+                    "\n{} {}",
+                    v.name,
+                    types::TypeId::Function(v.types.clone())
+                ));
+                if let Some(d) = &v.doc {
+                    s.push_str(&format!(" -- {d}"));
+                }
+            }
+            return Some(s.trim_start().to_string());
+        }
+        if let Some((ty, vn)) = name.split_once(':') {
+            let ty = types::TypeId::from(ty);
+            let vn = vn.strip_prefix(':').unwrap_or(vn);
+            return self.fn_map.get_doc(&ty, vn);
+        }
+        self.vr_map.get_doc(name)
+    }
+}
+
+fn push_field_docs(s: &mut String, fields: &[usertype::UserTypeField], indent: &str) {
+    for n in fields.iter() {
+        let p = if n.private { "::" } else { ":" };
+        s.push_str(&format!("\n{indent}{p}{} {}", n.name, n.ty));
+        if let Some(d) = &n.default {
+            s.push_str(&format!(" = {d}"));
+        }
+        if let Some(d) = &n.doc {
+            s.push_str(&format!(" -- {d}"));
+        }
     }
 }
 
@@ -76,17 +167,32 @@ pub fn parse_command_line_args(args: Vec<String>) -> Vec<value::Value> {
 ///
 /// If the result is `Ok`, it is guaranteed that all `tokens` are consumed.
 ///
+/// Doc comments held by outer blocks aren't visible in this block.
+///
 /// NOTE: Only top-level and lazy blocks should be passed `Some` for `params.args`.
 ///       In other words, evaluating an immediate block doesn't affect the argument list's stack.
 pub fn eval_block(
     env: &mut Environment,
     tokens: &mut Vec<Token>,
-    params: EnterLazyParams,
+    mut params: EnterLazyParams,
 ) -> RResult<Vec<value::Value>> {
     let pop_args = params.args.is_some();
+    let outer_doc = env.doc.take();
+    let outer_vars = params.captures.take().map(|n| {
+        // NOTE: `##` is taken over from the current scope instead of the captured frames,
+        //       because it's the subject of the function being executed.
+        if params.slf.is_none() {
+            params.slf = env.vr_map.get("##");
+        }
+        env.vr_map.enter(n)
+    });
     env.prepare_block_scope(params);
     let results = eval_block_directly(env, tokens);
     env.cleanup_block_scope(pop_args);
+    if let Some(n) = outer_vars {
+        env.vr_map.restore(n);
+    }
+    env.doc = outer_doc;
     results
 }
 
@@ -95,6 +201,12 @@ pub fn eval_block(
 /// * `env` - The current environment.
 /// * `tokens` - All tokens in the block in reverse order.
 ///
+/// A doc comment before a sentence is attached to the definition in the sentence.
+/// A doc comment at the end of the block is held for the next call.
+///
+/// A doc comment before a field symbol such as `':x` is attached to the field,
+/// which is passed to the sentence enclosing the block.
+///
 /// NOTE: This function does not manage the environment's variable map stack.
 ///       The caller is responsible for managing the stack.
 ///       This is to accommodate the behavior where top-level blocks in a REPL
@@ -106,13 +218,38 @@ pub fn eval_block_directly(
     let mut values = Vec::new();
     let mut caches = Vec::new();
     let mut dotted = false;
-    while !tokens.is_empty() || !caches.is_empty() {
+    let mut field_docs = Vec::new();
+    loop {
+        // NOTE: A doc comment is held until the end of the following sentence
+        //       so that the definition verb of the sentence can take it.
+        while let Some(Token::Doc(_)) = tokens.last() {
+            let Some(Token::Doc(n)) = tokens.pop() else {
+                unreachable!();
+            };
+            env.doc = match env.doc.take() {
+                Some(m) => Some(format!("{m}\n{n}")),
+                None => Some(n),
+            };
+        }
+        if tokens.is_empty() && caches.is_empty() {
+            break;
+        }
         let n = logic::eval_sentence(env, tokens, &mut caches, true)?.unwrap_or_default();
+        match (&n, env.doc.take()) {
+            (value::Value::Symbol(s), Some(d)) if s.starts_with(':') => {
+                field_docs.push((s.clone(), d));
+            }
+            // NOTE: Field docs in nested arrays are kept for the fields of variants.
+            (value::Value::Array(_), _) => field_docs.append(&mut env.field_docs),
+            _ => (),
+        }
+        env.field_docs.clear();
         values.push(n);
         dotted = matches!(tokens.last(), Some(Token::Dot)) && tokens.pop().is_some();
     }
     if dotted {
         values.push(value::Value::Nil);
     }
+    env.field_docs.append(&mut field_docs);
     Ok(values)
 }
@@ -1,5 +1,8 @@
 macro_rules! builtin_fn {
-    ($name: expr, $types: expr, $fn: expr) => {
+    ($name: expr, $types: expr, $fn: expr, [$($line: expr),* $(,)?]) => {
+        builtin_fn!($name, $types, $fn, [$($line),*].join("\n"))
+    };
+    ($name: expr, $types: expr, $fn: expr, $doc: expr) => {
         (
             $name.to_string(),
             Function {
@@ -7,6 +10,7 @@ macro_rules! builtin_fn {
                 private: false,
                 types: $types,
                 code: FunctionCode::Builtin($fn),
+                doc: Some($doc),
             },
         )
     };
@@ -32,21 +36,32 @@ macro_rules! pop_extract_variant {
 
 mod array;
 mod boolean;
+mod cmdline;
 mod cmp;
+mod document;
+mod field;
 mod lazy;
+mod map;
 mod numeric;
+mod option;
+mod pattern;
 mod print;
 mod string;
+mod symbol;
 mod variable;
+mod variant;
+
+pub use document::builtin_pages;
 
-use super::{types::*, value::Value, *};
+use super::{types::*, usertype::UserTypeMapStack, value::Value, variable::Captures, *};
 use crate::RResult;
 
 type BuiltinFunctionCode = fn(&mut Environment, Value, Vec<Value>) -> RResult<Value>;
 
 pub enum TypesCheckResult {
     Undecided,
-    Ok,
+    /// The arguments are decided, with the code of the overload to call.
+    Ok(FunctionCode),
     Err(String),
 }
 
@@ -56,19 +71,25 @@ pub struct Function {
     private: bool,
     types: Vec<TypeId>,
     code: FunctionCode,
+    doc: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum FunctionCode {
     Builtin(BuiltinFunctionCode),
-    UserDefined(Vec<Token>),
+    /// The type and tokens of the function with the variable frames visible where it's created
+    /// and the default values of its optional parameters.
+    UserDefined((TypeId, Vec<Token>, Captures, Vec<Value>)),
 }
 
-type FunctionMap = HashMap<TypeId, HashMap<String, Function>>;
+/// Overloads of functions for each type and verb name.
+type FunctionMap = HashMap<TypeId, HashMap<String, Vec<Function>>>;
 
 #[derive(Default)]
 pub struct FunctionMapStack {
     builtins: FunctionMap,
+    /// Names of builtin functions in the order of insertion, used to generate documents.
+    builtin_names: HashMap<TypeId, Vec<String>>,
     users: Vec<FunctionMap>,
 }
 
@@ -86,71 +107,176 @@ impl FunctionMapStack {
                 .last_mut()
                 .unwrap()
                 .insert(n.clone(), HashMap::new());
-            cmp::insert(self, n);
             print::insert(self, n);
             variable::insert(self, n);
+            option::insert_constructors(self, n);
+            pattern::insert(self, n);
         }
         array::insert(self);
         boolean::insert(self);
+        cmdline::insert(self);
         lazy::insert(self);
+        map::insert(self);
         numeric::insert(self);
+        option::insert(self);
         string::insert(self);
+        symbol::insert(self);
+        for n in ALL_PREMITIVE_TYPES {
+            cmp::insert(self, n);
+        }
     }
 
     pub fn pop(&mut self) {
         self.users.pop();
     }
 
-    fn get(&self, ty: &TypeId, vn: &str) -> Option<&Function> {
-        if let Some(n) = self.builtins.get(ty).and_then(|n| n.get(vn)) {
-            Some(n)
-        } else {
-            self.users.iter().rev().find_map(|n| n.get(ty)?.get(vn))
-        }
+    /// A function to get the builtin function of the verb on the type.
+    fn get_builtin(&self, ty: &TypeId, vn: &str) -> Option<&Function> {
+        self.builtins.get(ty)?.get(vn)?.first()
+    }
+
+    /// A function to get all overloads of the verb on the type,
+    /// the builtin first and then user-defined ones from the outermost scope.
+    fn get_overloads(&self, ty: &TypeId, vn: &str) -> Vec<&Function> {
+        std::iter::once(&self.builtins)
+            .chain(self.users.iter())
+            .filter_map(|n| n.get(ty)?.get(vn))
+            .flatten()
+            .collect()
+    }
+
+    /// A function to get the overloads callable in functions of the type `sty`.
+    fn get_visible_overloads(&self, sty: Option<&TypeId>, ty: &TypeId, vn: &str) -> Vec<&Function> {
+        self.get_overloads(ty, vn)
+            .into_iter()
+            .filter(|n| !n.private || sty == Some(ty))
+            .collect()
     }
 
     pub fn is_defined(&self, sty: Option<TypeId>, ty: &TypeId, vn: &str) -> bool {
-        self.get(ty, vn)
-            .map(|n| !n.private || sty.map(|n| &n == ty).unwrap_or(false))
-            .unwrap_or(false)
+        !self.get_visible_overloads(sty.as_ref(), ty, vn).is_empty()
     }
 
-    pub fn check_types(&self, ty: &TypeId, vn: &str, values: &[Value]) -> TypesCheckResult {
+    /// A function to narrow the overloads by the arguments collected so far.
+    ///
+    /// Returns the code of the overload that takes exactly `values`,
+    /// or `Undecided` if some overloads need more arguments.
+    /// An overload that can take more arguments is only decided at the end of the clause.
+    pub fn check_types(
+        &self,
+        ut_map: &UserTypeMapStack,
+        sty: Option<&TypeId>,
+        ty: &TypeId,
+        vn: &str,
+        values: &[Value],
+        at_end: bool,
+    ) -> TypesCheckResult {
         let len = values.len();
-        let expected = &self
-            .get(ty, vn)
-            .unwrap_or_else(|| panic!("{vn} on {ty} not defined."))
-            .types;
-
-        if len > expected.len() {
-            panic!("too many arguments passed.");
+        let overloads = self.get_visible_overloads(sty, ty, vn);
+        if overloads.is_empty() {
+            panic!("{vn} on {ty} not defined.");
         }
 
-        for (i, (n, m)) in values
-            .iter()
-            .map(|n| n.typeid())
-            .zip(expected.iter())
-            .enumerate()
-        {
-            if &n != m && m != &TypeId::Any {
-                return TypesCheckResult::Err(format!(
-                    "error: {vn} on {ty} expects {m} for #{i} but got {n}."
+        let mut undecided = false;
+        let mut error = None;
+        for f in overloads.iter() {
+            let expected = &f.types;
+            let (min, max) = arity(expected);
+            if max.is_some_and(|n| len > n) {
+                continue;
+            }
+            let mut bindings = TypeBindings::new();
+            let mismatched = values.iter().enumerate().find_map(|(i, n)| {
+                let t = param_type(expected, i).expect("too many arguments passed.");
+                (!n.unify(t, &mut bindings, ut_map)).then_some((i, t))
+            });
+            if let Some((i, t)) = mismatched {
+                error = Some(format!(
+                    "error: {vn} on {ty} expects {} for #{i} but got {}.",
+                    t.format_instantiated(&bindings),
+                    values[i].typeid_in_detail()
                 ));
+            } else if len < min || (!at_end && max.is_none_or(|n| len < n)) {
+                undecided = true;
+            } else {
+                return TypesCheckResult::Ok(f.code.clone());
             }
         }
 
-        if len < expected.len() {
+        if undecided {
             TypesCheckResult::Undecided
+        } else if overloads.len() == 1 {
+            TypesCheckResult::Err(error.expect("too many arguments passed."))
         } else {
-            TypesCheckResult::Ok
+            let types = values.iter().map(|n| n.typeid_in_detail()).collect();
+            TypesCheckResult::Err(format!(
+                "error: no overload of {vn} on {ty} matches the arguments {}.",
+                TypeId::Function(types)
+            ))
+        }
+    }
+
+    /// A function to get the type of the next argument if all overloads needing it agree,
+    /// where type variables bound by `values` are replaced with their types.
+    pub fn get_arg_type(
+        &self,
+        ut_map: &UserTypeMapStack,
+        sty: Option<&TypeId>,
+        ty: &TypeId,
+        vn: &str,
+        values: &[Value],
+    ) -> Option<TypeId> {
+        let mut result: Option<TypeId> = None;
+        for f in self.get_visible_overloads(sty, ty, vn) {
+            let mut bindings = TypeBindings::new();
+            if !values
+                .iter()
+                .zip(f.types.iter())
+                .all(|(n, m)| n.unify(m, &mut bindings, ut_map))
+            {
+                continue;
+            }
+            let Some(n) = param_type(&f.types, values.len()) else {
+                continue;
+            };
+            let n = n.substitute(&bindings);
+            match &result {
+                Some(m) if m != &n => return None,
+                _ => result = Some(n),
+            }
         }
+        result
+    }
+
+    /// A function to get the document of the verb.
+    ///
+    /// If the verb is overloaded, the documents of all overloads are listed with their types.
+    pub fn get_doc(&self, ty: &TypeId, vn: &str) -> Option<String> {
+        let overloads = self.get_overloads(ty, vn);
+        if let [f] = overloads.as_slice() {
+            return f.doc.clone();
+        }
+        if overloads.is_empty() {
+            return None;
+        }
+        let v = overloads
+            .iter()
+            .map(|f| {
+                let t = TypeId::Function(f.types.clone());
+                match &f.doc {
+                    Some(d) => format!("{t}\n{d}"),
+                    None => t.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        Some(v.join("\n"))
     }
 
-    pub fn get_code(&self, ty: &TypeId, vn: &str) -> FunctionCode {
-        self.get(ty, vn)
-            .unwrap_or_else(|| panic!("{vn} on {ty} not defined."))
-            .code
-            .clone()
+    pub fn get_builtin_names(&self, ty: &TypeId) -> &[String] {
+        self.builtin_names
+            .get(ty)
+            .map(|n| n.as_slice())
+            .unwrap_or_default()
     }
 
     fn insert_new_type(&mut self, ty: TypeId) {
@@ -162,7 +288,18 @@ impl FunctionMapStack {
         }
     }
 
-    fn insert_user_defined(&mut self, ty: &TypeId, vn: String, fun: Function) -> RResult<()> {
+    /// A function to define a user-defined function.
+    ///
+    /// A function with the same argument types as an existing overload replaces it,
+    /// and other functions are added as overloads
+    /// unless some arguments could be accepted by both of them.
+    fn insert_user_defined(
+        &mut self,
+        ut_map: &UserTypeMapStack,
+        ty: &TypeId,
+        vn: String,
+        fun: Function,
+    ) -> RResult<()> {
         if !self.users.iter().any(|n| n.contains_key(ty)) {
             return Err(format!("error: the type {ty} is not defined.").into());
         }
@@ -170,16 +307,33 @@ impl FunctionMapStack {
             .users
             .iter_mut()
             .rev()
-            .find_map(|n| n.get_mut(ty)?.get_mut(&vn))
+            .filter_map(|n| n.get_mut(ty)?.get_mut(&vn))
+            .flatten()
+            .find(|n| n.types == fun.types)
         {
             *n = fun;
-        } else {
-            let n = self.users.last_mut().expect("function map stack is empty.");
-            if !n.contains_key(ty) {
-                n.insert(ty.clone(), HashMap::new());
-            }
-            n.get_mut(ty).unwrap().insert(vn, fun);
+            return Ok(());
+        }
+        if let Some(n) = self
+            .get_overloads(ty, &vn)
+            .into_iter()
+            .find(|n| is_ambiguous(&n.types, &fun.types, ut_map))
+        {
+            return Err(format!(
+                "error: {vn} on {ty} taking {} is ambiguous with the one taking {}.",
+                TypeId::Function(fun.types),
+                TypeId::Function(n.types.clone())
+            )
+            .into());
         }
+        self.users
+            .last_mut()
+            .expect("function map stack is empty.")
+            .entry(ty.clone())
+            .or_default()
+            .entry(vn)
+            .or_default()
+            .push(fun);
         Ok(())
     }
 
@@ -192,7 +346,42 @@ impl FunctionMapStack {
             .get_mut(ty)
             .unwrap_or_else(|| panic!("function map for {ty} not inserted."));
         n.reserve(funs.len());
-        n.extend(funs);
+        let names = self.builtin_names.entry(ty.clone()).or_default();
+        for (vn, fun) in funs {
+            if !n.contains_key(&vn) {
+                names.push(vn.clone());
+            }
+            n.insert(vn, vec![fun]);
+        }
+    }
+}
+
+/// A function to check if some arguments could be accepted by both of the overloads,
+/// where the shorter one is compared with the beginning of the longer one
+/// because the collection of arguments cannot decide whether to continue.
+fn is_ambiguous(a: &[TypeId], b: &[TypeId], ut_map: &UserTypeMapStack) -> bool {
+    (0..a.len().max(b.len()))
+        .map_while(|i| Some((param_type(a, i)?, param_type(b, i)?)))
+        .all(|(n, m)| overlaps(n, m, ut_map))
+}
+
+/// A function to check if a value could be of both of the types.
+fn overlaps(a: &TypeId, b: &TypeId, ut_map: &UserTypeMapStack) -> bool {
+    match (a, b) {
+        _ if a == b => true,
+        (TypeId::Any | TypeId::Var(_), _) | (_, TypeId::Any | TypeId::Var(_)) => true,
+        // NOTE: An empty array is of any array type.
+        (TypeId::Array | TypeId::ArrayOf(_), TypeId::Array | TypeId::ArrayOf(_)) => true,
+        (TypeId::Optional(n), TypeId::Optional(m)) | (TypeId::Variadic(n), TypeId::Variadic(m)) => {
+            overlaps(n, m, ut_map)
+        }
+        (TypeId::Function(n), TypeId::Function(m)) => {
+            n.len() == m.len() && n.iter().zip(m.iter()).all(|(n, m)| overlaps(n, m, ut_map))
+        }
+        (TypeId::UserDefined(n), _) | (_, TypeId::UserDefined(n)) => {
+            ut_map.get_interface(n).is_some()
+        }
+        _ => false,
     }
 }
 
@@ -209,14 +398,144 @@ fn convert_index(i: i32, l: usize) -> RResult<usize> {
     }
 }
 
+/// A function to convert a start index and an optional end index into a range to slice with,
+/// where negative indices count from the end and the end is the length if omitted.
+fn convert_range(start: i32, end: Option<i32>, l: usize) -> RResult<std::ops::Range<usize>> {
+    let n = l as i32;
+    let f = |i: i32| if i < 0 { i + n } else { i };
+    let (a, b) = (f(start), end.map(f).unwrap_or(n));
+    if 0 <= a && a <= b && b <= n {
+        Ok(a as usize..b as usize)
+    } else {
+        let end = end.unwrap_or(n);
+        Err(format!(
+            "error: range must be within 0 <= start <= end <= {l} but passed {start} and {end}."
+        )
+        .into())
+    }
+}
+
+/// The argument types of slice functions, which are a start index and an optional end index.
+fn slice_types() -> Vec<TypeId> {
+    vec![TypeId::I32, TypeId::Optional(Box::new(TypeId::I32))]
+}
+
 fn convert_symbols_to_typeids(n: &[Value]) -> RResult<Vec<TypeId>> {
+    convert_params(n).map(|(n, _)| n)
+}
+
+/// A function to convert a type list into the parameter types and the default values.
+///
+/// In addition to types, the type list can contain the following parameters:
+///
+/// * `#['type default]` - An optional parameter, which must follow the required ones.
+/// * `'type*` - A variadic parameter, which must be the last.
+fn convert_params(list: &[Value]) -> RResult<(Vec<TypeId>, Vec<Value>)> {
     let mut v = Vec::new();
-    for n in n {
-        match n {
-            Value::Symbol(n) => v.push(TypeId::from(n)),
-            Value::Array(n) => v.push(TypeId::Function(convert_symbols_to_typeids(n)?)),
+    let mut defaults = Vec::new();
+    for (i, n) in list.iter().enumerate() {
+        let t = match n {
+            Value::Symbol(s) if s.len() > 1 && s.ends_with('*') => {
+                if i != list.len() - 1 {
+                    return Err("error: a variadic parameter must be the last.".into());
+                }
+                TypeId::Variadic(Box::new(TypeId::from(&s[..s.len() - 1])))
+            }
+            Value::Symbol(n) => TypeId::from(n),
+            Value::Array(n) => TypeId::Function(convert_symbols_to_typeids(n)?),
+            Value::Map(n) => {
+                let [(Value::Symbol(t), d)] = n.as_slice() else {
+                    return Err("error: an optional parameter must be a map of a type symbol and the default value.".into());
+                };
+                defaults.push(d.clone());
+                TypeId::Optional(Box::new(TypeId::from(t)))
+            }
             _ => return Err(format!("error: the element of type list must be symbol or array of symbols but passed '{}'.", n.typeid()).into()),
+        };
+        if !defaults.is_empty() && !matches!(t, TypeId::Optional(_) | TypeId::Variadic(_)) {
+            return Err("error: a required parameter cannot follow optional ones.".into());
+        }
+        v.push(t);
+    }
+    Ok((v, defaults))
+}
+
+/// A function to arrange the arguments as the parameters of the function type,
+/// filling the omitted optional parameters with the default values
+/// and collecting the rest of the arguments into an array for the variadic parameter.
+///
+/// * `args` - The arguments in order, which must be accepted by the function type.
+pub fn fill_args(types: &[TypeId], defaults: &[Value], args: Vec<Value>) -> Vec<Value> {
+    let mut args = args.into_iter();
+    let mut defaults = defaults.iter();
+    let mut v = Vec::with_capacity(types.len());
+    for t in types {
+        match t {
+            TypeId::Optional(_) => {
+                let d = defaults.next().expect("default value not found.");
+                v.push(args.next().unwrap_or_else(|| d.clone()));
+            }
+            TypeId::Variadic(_) => v.push(Value::Array(args.by_ref().collect())),
+            _ => v.push(args.next().expect("too few arguments passed.")),
+        }
+    }
+    v
+}
+
+/// Literals of a type used in examples of builtin documents.
+struct Example {
+    less: String,
+    greater: String,
+    /// How `less` is output by `!`.
+    printed: String,
+}
+
+impl Example {
+    fn of(ty: &TypeId) -> Option<Self> {
+        let (less, greater, printed) = match ty {
+            TypeId::Bool => ("()".to_string(), "T".to_string(), "()"),
+            TypeId::I32 => ("1".to_string(), "2".to_string(), "1"),
+            TypeId::F32 => ("1.5f32".to_string(), "2.5f32".to_string(), "1.5"),
+            TypeId::F64 => ("1.5".to_string(), "2.5".to_string(), "1.5"),
+            TypeId::I8
+            | TypeId::U8
+            | TypeId::I16
+            | TypeId::U16
+            | TypeId::U32
+            | TypeId::I64
+            | TypeId::U64
+            | TypeId::I128
+            | TypeId::U128 => (format!("1{ty}"), format!("2{ty}"), "1"),
+            TypeId::String => ("\"bar\"".to_string(), "\"baz\"".to_string(), "bar"),
+            TypeId::Symbol => ("'bar".to_string(), "'baz".to_string(), "bar"),
+            TypeId::Array => ("[1 2]".to_string(), "[1 3]".to_string(), "[1 2]"),
+            TypeId::Map => ("#['a 1]".to_string(), "#['a 2]".to_string(), "#[a 1]"),
+            TypeId::Lazy => ("{ 1 + 2 }".to_string(), "{ 2 + 1 }".to_string(), "{}"),
+            TypeId::Option => ("(1 ?+)".to_string(), "(2 ?+)".to_string(), "some[1]"),
+            TypeId::Result => ("(1 !+)".to_string(), "(2 !+)".to_string(), "ok[1]"),
+            _ => return None,
+        };
+        Some(Self {
+            less,
+            greater,
+            printed: printed.to_string(),
+        })
+    }
+}
+
+/// A function to format lines of code with comments as a code block of a builtin document.
+///
+/// A line without code continues the comment of the previous line.
+fn format_example(lines: &[(String, String)]) -> String {
+    let width = lines.iter().map(|(n, _)| n.len()).max().unwrap_or_default();
+    let mut s = "```fuzzy\n".to_string();
+    for (code, comment) in lines {
+        if comment.is_empty() {
+            s.push_str(&format!("{code}\n"));
+        } else {
+            s.push_str(&format!("{code:width$} -- {comment}\n"));
         }
     }
-    Ok(v)
+    s.push_str("```");
+    s
 }
@@ -1,20 +1,577 @@
-use super::{super::usertype::UserTypeField, value::Object, *};
+use super::{
+    super::usertype::{
+        Interface, InterfaceVerb, UserType, UserTypeField, UserTypeMapStack, UserTypeVariant,
+    },
+    error::TypeError,
+    value::Object,
+    *,
+};
+use std::cmp::Ordering;
 
 pub fn insert(fm: &mut FunctionMapStack) {
     fm.insert_builtins(
         &TypeId::Array,
         vec![
-            builtin_fn!("#", vec![], length),
-            builtin_fn!("^", vec![], first),
-            builtin_fn!("$", vec![], last),
-            builtin_fn!("@", vec![TypeId::I32], at),
-            builtin_fn!("@@", vec![TypeId::I32, TypeId::Any], replace),
-            builtin_fn!("@<", vec![TypeId::I32, TypeId::Any], ins),
-            builtin_fn!("@-", vec![TypeId::I32], remove),
-            builtin_fn!("$>", vec![TypeId::Any], push),
-            builtin_fn!("$-", vec![], pop),
-            builtin_fn!("|>", vec![TypeId::Symbol], define_user_type),
-            builtin_fn!(":", vec![TypeId::Symbol], cast_to_user_type),
+            builtin_fn!(
+                "#",
+                vec![],
+                length,
+                [
+                    "Returns the length of the array.",
+                    "The return type is `'u32`.",
+                    "",
+                    "```fuzzy",
+                    "[] #      -- 0",
+                    "[1 2 3] # -- 3",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "^",
+                vec![],
+                first,
+                [
+                    "Returns the first element.",
+                    "Returns `()` if the array is empty.",
+                    "",
+                    "```fuzzy",
+                    "[] ^           -- ()",
+                    "[1 2 3] ^      -- 1",
+                    "['foo \"bar\"] ^ -- 'foo",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "$",
+                vec![],
+                last,
+                [
+                    "Returns the last element.",
+                    "Returns `()` if the array is empty.",
+                    "",
+                    "```fuzzy",
+                    "[] $           -- ()",
+                    "[1 2 3] $      -- 3",
+                    "['foo \"bar\"] ^ -- \"bar\"",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@",
+                vec![TypeId::I32],
+                at,
+                [
+                    "Returns the element at the specified index.",
+                    "Returns `()` if the index is out of bounds.",
+                    "",
+                    "```fuzzy",
+                    "[4 5 6] @ 1   -- 5",
+                    "[4 5 6] @ -1  -- 6",
+                    "[4 5 6] @ 100 -- ()",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@?",
+                vec![TypeId::I32],
+                try_at,
+                [
+                    "Returns the element at the specified index as an option.",
+                    "Returns `none[]` if the index is out of bounds.",
+                    "",
+                    "```fuzzy",
+                    "[4 5 6] @? 1   -- some[5]",
+                    "[4 () 6] @? 1  -- some[()]",
+                    "[4 5 6] @? 100 -- none[]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@@",
+                vec![TypeId::I32, TypeId::Any],
+                replace,
+                [
+                    "Replaces the element at the specified index with the object.",
+                    "",
+                    "```fuzzy",
+                    "[4 5 6] @@ 1 \"hey\" -- [4 \"hey\" 6]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@<",
+                vec![TypeId::I32, TypeId::Any],
+                ins,
+                [
+                    "Inserts an element at the specified index.",
+                    "",
+                    "```fuzzy",
+                    "['foo \"baz\"] @< 1 \"bar\" -- ['foo \"bar\" \"baz\"]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@-",
+                vec![TypeId::I32],
+                remove,
+                [
+                    "Removes the element at the specified index.",
+                    "",
+                    "```fuzzy",
+                    "['foo \"bar\" \"baz\"] @- 1 -- ['foo \"baz\"]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "$-",
+                vec![],
+                pop,
+                [
+                    "Removes the last element.",
+                    "",
+                    "```fuzzy",
+                    "['foo \"bar\" \"baz\"] $- -- ['foo \"bar\"]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "$>",
+                vec![TypeId::Any],
+                push,
+                [
+                    "Appends an element to the end.",
+                    "",
+                    "```fuzzy",
+                    "['foo \"bar\"] $> \"baz\" -- ['foo \"bar\" \"baz\"]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@:",
+                slice_types(),
+                slice,
+                [
+                    "Returns the elements from the start index to before the end index.",
+                    "Negative indices count from the end, and the end index can be omitted to take the rest.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3 4] @: 1 3  -- [2 3]",
+                    "[1 2 3 4] @: -2   -- [3 4]",
+                    "[1 2 3 4] @: 0 -1 -- [1 2 3]",
+                    "[1 2 3 4] @: 3 1  -- error",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "++",
+                vec![TypeId::Array],
+                concat,
+                [
+                    "Concatenates the object to the end of the subject.",
+                    "",
+                    "```fuzzy",
+                    "[1 2] ++ [3 4] -- [1 2 3 4]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "**",
+                vec![TypeId::U32],
+                repeat,
+                [
+                    "Returns an array repeating the elements of the subject the specified number of times.",
+                    "",
+                    "```fuzzy",
+                    "[1 2] ** 3u32 -- [1 2 1 2 1 2]",
+                    "[1 2] ** 0u32 -- []",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%@",
+                vec![TypeId::Any],
+                map,
+                [
+                    "Returns an array of the results of calling the object with each element.",
+                    "The object must be a function taking one argument.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] %@ { #0 * 2 } : ['i32] -- [2 4 6]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%?",
+                vec![TypeId::Any],
+                filter,
+                [
+                    "Returns an array of the elements for which the object returns a value other than `()`.",
+                    "The object must be a function taking one argument.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3 4] %? { (#0 % 2) == 0 } : ['i32] -- [2 4]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%/",
+                vec![TypeId::Any, TypeId::Any],
+                fold,
+                [
+                    "Folds the elements from the first with the first object as the initial value.",
+                    "The second object must be a function taking the accumulated value and an element,",
+                    "and returning the next accumulated value.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] %/ 0 { #0 + #1 } : ['i32 'i32]      -- 6",
+                    "[1 2 3] %/ [] { #0 $> #1 } : ['[] 'i32]     -- [1 2 3]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%%",
+                vec![TypeId::Any],
+                for_each,
+                [
+                    "Calls the object with each element in order.",
+                    "The object must be a function taking one argument.",
+                    "Returns `()`.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] %% { #0 ! } : ['i32] -- outputs 123",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%|",
+                vec![TypeId::Any],
+                any,
+                [
+                    "Returns `T` if the object returns a value other than `()` for any element, otherwise `()`.",
+                    "The object must be a function taking one argument.",
+                    "The object is not called for the elements after the first one satisfying it.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] %| { #0 > 2 } : ['i32] -- T",
+                    "[] %| { #0 > 2 } : ['i32]      -- ()",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%&",
+                vec![TypeId::Any],
+                all,
+                [
+                    "Returns `T` if the object returns a value other than `()` for all elements, otherwise `()`.",
+                    "The object must be a function taking one argument.",
+                    "The object is not called for the elements after the first one not satisfying it.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] %& { #0 > 2 } : ['i32] -- ()",
+                    "[] %& { #0 > 2 } : ['i32]      -- T",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%^",
+                vec![TypeId::Any],
+                find,
+                [
+                    "Returns the first element for which the object returns a value other than `()` as an option.",
+                    "The object must be a function taking one argument.",
+                    "Returns `none[]` if no element satisfies it.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] %^ { #0 > 1 } : ['i32] -- some[2]",
+                    "[1 2 3] %^ { #0 > 5 } : ['i32] -- none[]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%*",
+                vec![TypeId::Any],
+                flat_map,
+                [
+                    "Returns an array concatenating the arrays returned by calling the object with each element.",
+                    "The object must be a function taking one argument and returning an array.",
+                    "",
+                    "```fuzzy",
+                    "[1 2] %* { [#0 #0] } : ['i32] -- [1 1 2 2]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "<<",
+                vec![],
+                sort,
+                [
+                    "Sorts the elements in ascending order, keeping the order of equal elements.",
+                    "Arrays are compared lexicographically.",
+                    "An error is thrown if the elements cannot be compared with each other.",
+                    "",
+                    "```fuzzy",
+                    "[3 1 2] <<           -- [1 2 3]",
+                    "[[2 1] [1 3] [1]] << -- [[1] [1 3] [2 1]]",
+                    "[1 \"a\"] <<           -- error",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "<@",
+                vec![TypeId::Any],
+                sort_by_key,
+                [
+                    "Sorts the elements in ascending order of the keys returned by the object,",
+                    "keeping the order of elements with equal keys.",
+                    "The object must be a function taking one argument.",
+                    "",
+                    "```fuzzy",
+                    "[\"ccc\" \"a\" \"bb\"] <@ { #0 # } : ['string] -- [\"a\" \"bb\" \"ccc\"]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "<%",
+                vec![TypeId::Any],
+                sort_with,
+                [
+                    "Sorts the elements with the object, keeping the order of equal elements.",
+                    "The object must be a function taking two elements,",
+                    "which returns a value other than `()` if the first must be placed before the second.",
+                    "",
+                    "```fuzzy",
+                    "[1 3 2] <% { #0 > #1 } : ['i32 'i32] -- [3 2 1]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "><",
+                vec![],
+                reverse,
+                [
+                    "Reverses the order of the elements.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] >< -- [3 2 1]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "</",
+                vec![TypeId::Any],
+                binary_search,
+                [
+                    "Searches the sorted array for the object.",
+                    "Returns a success of the index if found,",
+                    "otherwise an error of the index where the object can be inserted keeping the order.",
+                    "",
+                    "```fuzzy",
+                    "[1 3 5] </ 3 -- ok[1]",
+                    "[1 3 5] </ 4 -- err[2]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@=",
+                vec![TypeId::Any],
+                index_of,
+                [
+                    "Returns the index of the first element equal to the object as an option.",
+                    "Returns `none[]` if not found.",
+                    "",
+                    "```fuzzy",
+                    "['a 'b 'c] @= 'b -- some[1]",
+                    "['a 'b 'c] @= 'd -- none[]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "=?",
+                vec![TypeId::Any],
+                contains,
+                [
+                    "Checks if the array has an element equal to the object.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] =? 2 -- T",
+                    "[1 2 3] =? 4 -- ()",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "=-",
+                vec![],
+                dedup,
+                [
+                    "Removes the elements equal to an earlier one.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 1 3 2] =- -- [1 2 3]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "|",
+                vec![TypeId::Array],
+                union,
+                [
+                    "Returns the union of the subject and the object as sets,",
+                    "which has the elements of the subject followed by the ones only in the object.",
+                    "Elements equal to an earlier one are removed.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] | [2 4] -- [1 2 3 4]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "&",
+                vec![TypeId::Array],
+                intersection,
+                [
+                    "Returns the intersection of the subject and the object as sets,",
+                    "which has the elements of the subject also in the object.",
+                    "Elements equal to an earlier one are removed.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] & [2 4] -- [2]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "-",
+                vec![TypeId::Array],
+                difference,
+                [
+                    "Returns the difference of the subject and the object as sets,",
+                    "which has the elements of the subject not in the object.",
+                    "Elements equal to an earlier one are removed.",
+                    "",
+                    "```fuzzy",
+                    "[1 2 3] - [2 4] -- [1 3]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "|>",
+                vec![TypeId::Symbol],
+                define_user_type,
+                [
+                    "Defines a user-defined type.",
+                    "The subject must be an array that satisfies the following requirements:",
+                    "",
+                    "- All leaf elements must be of type `'symbol'`",
+                    "- Odd-numbered symbols represent member names",
+                    "- Odd-numbered symbols must have a visibility prefix `:` or `::`",
+                    "- Even-numbered elements represent member types",
+                    "- If an even-numbered element is an array, it represents a function type",
+                    "- A doc comment `---` before a member name is the document of the member",
+                    "- `'=` and a value after a member type is the default value of the member",
+                    "- `'?` and a function after a member type is the validator of the member,",
+                    "  which returns `()` if the value is invalid",
+                    "- `'@` and a lazy block is the constructor",
+                    "",
+                    "On `: 'typename`, omitted members take their default values",
+                    "and an error is thrown if a validator returns `()`.",
+                    "Then the constructor is evaluated with the value as `##`,",
+                    "and its result is used instead, which is a value of the type or a `'result` of it.",
+                    "",
+                    "The defined user-defined type cannot be redefined until the scope is exited.",
+                    "",
+                    "```fuzzy",
+                    "[':foo 'i32. '::bar ['i32]] |> 'newtype. -- defines a user-defined type newtype",
+                    "                                         -- with a public member foo of type 'i32",
+                    "                                         -- and a private member bar of type @['i32]",
+                    "[':x 'i32 '= 0 '? { #0 >= 0 } : ['i32]] |> 'natural.",
+                    "[] : 'natural                            -- [:x 0]",
+                    "[':x -1] : 'natural                      -- error",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "|+",
+                vec![TypeId::Symbol],
+                define_sum_type,
+                [
+                    "Defines a sum type, whose value is one of its variants.",
+                    "The subject must be an array that satisfies the following requirements:",
+                    "",
+                    "- Each variant is a symbol of its name followed by an array of its fields",
+                    "- Fields of a variant are written in the same way as `|>`",
+                    "- A string after the fields is the document of the variant",
+                    "",
+                    "A value of a variant is created by casting with `'typename:variantname`.",
+                    "`??` on a sum type evaluates the block associated with the variant of the subject.",
+                    "",
+                    "```fuzzy",
+                    "['circle [':radius 'f64] 'rect [':width 'f64 ':height 'f64]] |+ 'shape.",
+                    "[':radius 2.0] : 'shape:circle, -> 's.",
+                    "s !!                               -- circle[:radius 2]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "|&",
+                vec![TypeId::Symbol],
+                define_interface,
+                [
+                    "Defines an interface, which is a named set of verbs.",
+                    "The subject must be an array that satisfies the following requirements:",
+                    "",
+                    "- Each verb is a symbol of its name followed by an array of its argument types",
+                    "- A string after the argument types is the document of the verb",
+                    "",
+                    "A type is declared to implement the interface by `&>` on `'symbol`.",
+                    "The interface name can be used as an argument type,",
+                    "and accepts values of the types implementing the interface.",
+                    "Verbs called on such arguments are the ones of their own types.",
+                    "",
+                    "```fuzzy",
+                    "['area [] \"Returns the area.\"] |& 'has-area.",
+                    "[':side 'f64] |> 'square.",
+                    "{ ##:side * ##:side } : [], -> 'square:area.",
+                    "'square &> 'has-area.",
+                    "{ #0 area * 2.0 } : ['has-area], -> 'double-area.",
+                    "double-area @ ([':side 3.0] : 'square) -- 18",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                ":",
+                vec![TypeId::Symbol],
+                cast_to_user_type,
+                [
+                    "Casts the subject to the user-defined type indicated by the object.",
+                    "The subject must be an array that satisfies the following requirements:",
+                    "",
+                    "- Odd-numbered symbols represent member names",
+                    "- Odd-numbered symbols must have a visibility prefix `:` or `::`",
+                    "- Even-numbered elements are member values",
+                    "",
+                    "If the type is a sum type, the object must be `'typename:variantname`.",
+                    "",
+                    "```fuzzy",
+                    "[':foo 12 '::bar {#0 !!} : ['i32]] : 'newtype, -> 'var. -- defines a variable var of type 'newtype",
+                    "var:foo                                                 -- 12",
+                    "var:bar @ 1                                             -- error because bar is private",
+                    "{ ##::bar @ 1 } : [], -> 'newtype:baz.                  -- define a function on newtype",
+                    "var baz.                                                -- to access bar",
+                    "                                                        -- outputs 1",
+                    "```",
+                ]
+            ),
+        ],
+    );
+    option::insert_try_cast(
+        fm,
+        &TypeId::Array,
+        &[
+            ("[':x 'i32] |> 'point.", ""),
+            ("[':x 1] :? 'point", "ok[[:x 1]]"),
+            (
+                "[':x 1.5] :? 'point",
+                "err[field x expects type i32 but f64 provided.]",
+            ),
         ],
     );
 }
@@ -46,6 +603,15 @@ fn at(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     Ok(s[i].clone())
 }
 
+fn try_at(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, I32);
+    let n = convert_index(o, s.len())
+        .ok()
+        .map(|i| Box::new(s.swap_remove(i)));
+    Ok(Value::Option(n))
+}
+
 fn replace(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     let mut s = extract_variant!(s, Array);
     let o = pop_extract_variant!(args, I32);
@@ -85,13 +651,417 @@ fn pop(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
     Ok(Value::Array(s))
 }
 
+fn slice(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Array);
+    let start = pop_extract_variant!(args, I32);
+    let end = args.pop().map(|n| extract_variant!(n, I32));
+    let r = convert_range(start, end, s.len())?;
+    Ok(Value::Array(s.drain(r).collect()))
+}
+
+fn concat(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, Array);
+    s.extend(o);
+    Ok(Value::Array(s))
+}
+
+fn repeat(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, U32);
+    let n = s.len() * o as usize;
+    Ok(Value::Array(s.into_iter().cycle().take(n).collect()))
+}
+
+fn map(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    let v = s
+        .into_iter()
+        .map(|n| lazy::call_function(env, o.clone(), vec![n]))
+        .collect::<RResult<_>>()?;
+    Ok(Value::Array(v))
+}
+
+fn filter(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    let mut v = Vec::new();
+    for n in s {
+        if lazy::call_function(env, o.clone(), vec![n.clone()])? != Value::Nil {
+            v.push(n);
+        }
+    }
+    Ok(Value::Array(v))
+}
+
+fn fold(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let init = args.pop().expect("type missmatched.");
+    let o = args.pop().expect("type missmatched.");
+    s.into_iter().try_fold(init, |acc, n| {
+        lazy::call_function(env, o.clone(), vec![acc, n])
+    })
+}
+
+fn for_each(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    for n in s {
+        lazy::call_function(env, o.clone(), vec![n])?;
+    }
+    Ok(Value::Nil)
+}
+
+fn any(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    for n in s {
+        if lazy::call_function(env, o.clone(), vec![n])? != Value::Nil {
+            return Ok(Value::Top);
+        }
+    }
+    Ok(Value::Nil)
+}
+
+fn all(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    for n in s {
+        if lazy::call_function(env, o.clone(), vec![n])? == Value::Nil {
+            return Ok(Value::Nil);
+        }
+    }
+    Ok(Value::Top)
+}
+
+fn find(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    for n in s {
+        if lazy::call_function(env, o.clone(), vec![n.clone()])? != Value::Nil {
+            return Ok(Value::Option(Some(Box::new(n))));
+        }
+    }
+    Ok(Value::Option(None))
+}
+
+fn flat_map(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    let mut v = Vec::new();
+    for n in s {
+        match lazy::call_function(env, o.clone(), vec![n])? {
+            Value::Array(n) => v.extend(n),
+            n => {
+                return Err(format!(
+                    "error: the function passed to %* must return an array but returned {}.",
+                    n.typeid()
+                )
+                .into());
+            }
+        }
+    }
+    Ok(Value::Array(v))
+}
+
+fn sort(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let v = merge_sort(s, &mut |a, b| Ok(a.compare(b)? == Ordering::Less))?;
+    Ok(Value::Array(v))
+}
+
+fn sort_by_key(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    let keyed = s
+        .into_iter()
+        .map(|n| Ok((lazy::call_function(env, o.clone(), vec![n.clone()])?, n)))
+        .collect::<RResult<_>>()?;
+    let v = merge_sort(keyed, &mut |(a, _), (b, _)| {
+        Ok(a.compare(b)? == Ordering::Less)
+    })?;
+    Ok(Value::Array(v.into_iter().map(|(_, n)| n).collect()))
+}
+
+fn sort_with(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    let v = merge_sort(s, &mut |a, b| {
+        let n = lazy::call_function(env, o.clone(), vec![a.clone(), b.clone()])?;
+        Ok(n != Value::Nil)
+    })?;
+    Ok(Value::Array(v))
+}
+
+/// A function to sort stably with `less`, which checks if the first must be placed before the second.
+///
+/// NOTE: `slice::sort_by` is not used because `less` can fail.
+fn merge_sort<T>(mut v: Vec<T>, less: &mut impl FnMut(&T, &T) -> RResult<bool>) -> RResult<Vec<T>> {
+    if v.len() <= 1 {
+        return Ok(v);
+    }
+    let right = v.split_off(v.len() / 2);
+    let mut left = merge_sort(v, less)?.into_iter().peekable();
+    let mut right = merge_sort(right, less)?.into_iter().peekable();
+    let mut v = Vec::with_capacity(left.len() + right.len());
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        let n = if less(r, l)? {
+            right.next()
+        } else {
+            left.next()
+        };
+        v.extend(n);
+    }
+    v.extend(left);
+    v.extend(right);
+    Ok(v)
+}
+
+fn reverse(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Array);
+    s.reverse();
+    Ok(Value::Array(s))
+}
+
+fn binary_search(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    let (mut lo, mut hi) = (0, s.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match s[mid].compare(&o)? {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+            Ordering::Equal => return Ok(Value::Result(Ok(Box::new(Value::I32(mid as i32))))),
+        }
+    }
+    Ok(Value::Result(Err(Box::new(Value::I32(lo as i32)))))
+}
+
+fn index_of(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    let n = s
+        .iter()
+        .position(|n| is_same(n, &o))
+        .map(|i| Box::new(Value::I32(i as i32)));
+    Ok(Value::Option(n))
+}
+
+fn contains(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = args.pop().expect("type missmatched.");
+    if s.iter().any(|n| is_same(n, &o)) {
+        Ok(Value::Top)
+    } else {
+        Ok(Value::Nil)
+    }
+}
+
+fn dedup(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    Ok(Value::Array(unique(s)))
+}
+
+fn union(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, Array);
+    s.extend(o);
+    Ok(Value::Array(unique(s)))
+}
+
+fn intersection(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, Array);
+    let v = s
+        .into_iter()
+        .filter(|n| o.iter().any(|m| is_same(n, m)))
+        .collect();
+    Ok(Value::Array(unique(v)))
+}
+
+fn difference(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, Array);
+    let v = s
+        .into_iter()
+        .filter(|n| !o.iter().any(|m| is_same(n, m)))
+        .collect();
+    Ok(Value::Array(unique(v)))
+}
+
+/// A function to check if the values are of the same type and equal.
+fn is_same(a: &Value, b: &Value) -> bool {
+    a.typeid() == b.typeid() && a.equal(b)
+}
+
+/// A function to remove the values equal to an earlier one.
+fn unique(values: Vec<Value>) -> Vec<Value> {
+    let mut v: Vec<Value> = Vec::with_capacity(values.len());
+    for n in values {
+        if !v.iter().any(|m| is_same(m, &n)) {
+            v.push(n);
+        }
+    }
+    v
+}
+
 fn define_user_type(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     let s = extract_variant!(s, Array);
     let o = pop_extract_variant!(args, Symbol);
 
+    let mut docs = std::mem::take(&mut env.field_docs);
+    let (fields, constructor) = parse_field_definitions(&env.ut_map, &s, &mut docs)?;
+    let ut = UserType {
+        fields,
+        variants: Vec::new(),
+        constructor,
+        doc: env.doc.take(),
+    };
+    env.ut_map.insert(o.clone(), ut)?;
+    insert_user_type_functions(&mut env.fn_map, TypeId::UserDefined(o), false);
+
+    Ok(Value::Nil)
+}
+
+fn define_sum_type(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, Symbol);
+
+    let mut docs = std::mem::take(&mut env.field_docs);
+    let mut variants: Vec<UserTypeVariant> = Vec::new();
+    let mut i = 0;
+    while i < s.len() {
+        let Value::Symbol(n) = &s[i] else {
+            return Err("error: variant name must be a symbol.".into());
+        };
+        if n.starts_with(':') {
+            return Err("error: variant name must not start with ':'.".into());
+        }
+        if variants.iter().any(|v| &v.name == n) {
+            return Err(format!("error: the variant {n} is defined twice.").into());
+        }
+
+        i += 1;
+
+        let Some(Value::Array(f)) = s.get(i) else {
+            return Err(format!("error: the variant {n} must be followed by its fields.").into());
+        };
+        let (fields, constructor) = parse_field_definitions(&env.ut_map, f, &mut docs)?;
+        if constructor.is_some() {
+            return Err("error: a constructor cannot be defined for a variant.".into());
+        }
+
+        i += 1;
+
+        let d = if let Some(Value::String(d)) = s.get(i) {
+            i += 1;
+            Some(d.clone())
+        } else {
+            None
+        };
+
+        variants.push(UserTypeVariant {
+            name: n.clone(),
+            fields,
+            doc: d,
+        });
+    }
+    if variants.is_empty() {
+        return Err("error: a sum type must have at least one variant.".into());
+    }
+
+    let ut = UserType {
+        fields: Vec::new(),
+        variants,
+        constructor: None,
+        doc: env.doc.take(),
+    };
+    env.ut_map.insert(o.clone(), ut)?;
+    insert_user_type_functions(&mut env.fn_map, TypeId::UserDefined(o), true);
+
+    Ok(Value::Nil)
+}
+
+fn define_interface(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, Symbol);
+
+    if !matches!(TypeId::from(&o), TypeId::UserDefined(_)) {
+        return Err("error: cannot redefine type.".into());
+    }
+
+    let mut verbs: Vec<InterfaceVerb> = Vec::new();
+    let mut s = s.into_iter().peekable();
+    while let Some(n) = s.next() {
+        let Value::Symbol(n) = n else {
+            return Err("error: verb name must be a symbol.".into());
+        };
+        if verbs.iter().any(|v| v.name == n) {
+            return Err(format!("error: the verb {n} is defined twice.").into());
+        }
+        let Some(Value::Array(t)) = s.next() else {
+            return Err(
+                format!("error: the verb {n} must be followed by its argument types.").into(),
+            );
+        };
+        let doc = match s.next_if(|n| matches!(n, Value::String(_))) {
+            Some(Value::String(d)) => Some(d),
+            _ => None,
+        };
+        verbs.push(InterfaceVerb {
+            name: n,
+            types: convert_symbols_to_typeids(&t)?,
+            doc,
+        });
+    }
+
+    let interface = Interface {
+        verbs,
+        implementors: Vec::new(),
+        doc: env.doc.take(),
+    };
+    env.ut_map.insert_interface(o, interface)?;
+
+    Ok(Value::Nil)
+}
+
+fn insert_user_type_functions(fm: &mut FunctionMapStack, ty: TypeId, sum: bool) {
+    fm.insert_new_type(ty.clone());
+    print::insert(fm, &ty);
+    variable::insert(fm, &ty);
+    option::insert_constructors(fm, &ty);
+    pattern::insert(fm, &ty);
+    cmp::insert(fm, &ty);
+    field::insert(fm, &ty);
+    if sum {
+        variant::insert(fm, &ty);
+    }
+}
+
+/// A function to parse field definitions written in the subject of `|>`,
+/// returning the fields and the constructor following `'@`.
+///
+/// * `docs` - Doc comments of fields, from which the ones of the parsed fields are taken.
+fn parse_field_definitions(
+    ut_map: &UserTypeMapStack,
+    s: &[Value],
+    docs: &mut Vec<(String, String)>,
+) -> RResult<(Vec<UserTypeField>, Option<Value>)> {
     let mut fields = Vec::new();
+    let mut constructor = None;
     let mut i = 0;
     while i < s.len() {
+        if matches!(&s[i], Value::Symbol(n) if n == "@") {
+            let Some(n @ Value::Lazy(_)) = s.get(i + 1) else {
+                return Err("error: a constructor after '@ must be a lazy block.".into());
+            };
+            constructor = Some(n.clone());
+            i += 2;
+            continue;
+        }
+
         if i + 1 >= s.len() {
             return Err("error: field definition must have mutability, name and type.".into());
         }
@@ -99,6 +1069,10 @@ fn define_user_type(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RR
         let Value::Symbol(n) = &s[i] else {
             return Err("error: field name must be a symbol.".into());
         };
+        let d = docs
+            .iter()
+            .position(|(m, _)| m == n)
+            .map(|j| docs.remove(j).1);
         let (p, n) = if let Some(n) = n.strip_prefix("::") {
             (true, n.to_string())
         } else if let Some(n) = n.strip_prefix(":") {
@@ -116,33 +1090,70 @@ fn define_user_type(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RR
         } else {
             return Err("error: field type must be a symbol.".into());
         };
+        if t.is_generic() {
+            return Err(format!(
+                "error: the type of field {n} cannot contain type variables but {t} specified."
+            )
+            .into());
+        }
 
         i += 1;
 
+        let mut default = None;
+        let mut validator = None;
+        while let Some(Value::Symbol(m)) = s.get(i) {
+            let Some(v) = s.get(i + 1) else {
+                break;
+            };
+            match m.as_str() {
+                "=" => {
+                    if !v.is_of(&t, ut_map) {
+                        return Err(TypeError(format!(
+                            "error: the default value of field {n} expects type {t} but {} provided.",
+                            v.typeid_in_detail()
+                        ))
+                        .into());
+                    }
+                    default = Some(v.clone());
+                }
+                "?" => {
+                    if !matches!(v, Value::Function(_)) {
+                        return Err(format!(
+                            "error: the validator of field {n} must be a function."
+                        )
+                        .into());
+                    }
+                    validator = Some(v.clone());
+                }
+                _ => break,
+            }
+            i += 2;
+        }
+
         fields.push(UserTypeField {
             private: p,
             name: n,
             ty: t,
+            doc: d,
+            default,
+            validator,
         });
     }
-
-    env.ut_map.insert(o.clone(), fields)?;
-
-    let t = TypeId::UserDefined(o);
-    env.fn_map.insert_new_type(t.clone());
-    variable::insert(&mut env.fn_map, &t);
-
-    Ok(Value::Nil)
+    Ok((fields, constructor))
 }
 
 fn cast_to_user_type(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     let s = extract_variant!(s, Array);
     let o = pop_extract_variant!(args, Symbol);
+    let (o, vn) = match o.split_once(':') {
+        Some((n, m)) => (n.to_string(), Some(m.to_string())),
+        None => (o, None),
+    };
 
     let mut fields = HashMap::new();
     let mut i = 0;
     while i < s.len() {
-        if i + 1 > s.len() {
+        if i + 1 >= s.len() {
             return Err("error: field definition must have both name and value.".into());
         }
 
@@ -173,37 +1184,107 @@ fn cast_to_user_type(env: &mut Environment, s: Value, mut args: Vec<Value>) -> R
         );
     }
 
-    let Some(ut_fields) = env.ut_map.get(&o) else {
+    let Some(ut) = env.ut_map.get(&o) else {
         return Err(format!("error: the type {o} not defined.").into());
     };
+    // OPTIMIZE: remove clone.
+    let ut = ut.clone();
 
-    if fields.len() != ut_fields.len() {
-        return Err("error: The provided array does not match the type definition.".into());
-    }
-
-    for ut in ut_fields.iter() {
-        let Some(field) = fields.get_mut(&ut.name) else {
+    let ut_fields = match (&vn, ut.is_sum()) {
+        (None, false) => &ut.fields,
+        (None, true) => {
             return Err(format!(
-                "error: {} not found in user-type variable definition.",
-                ut.name
+                "error: {o} is a sum type, so the variant must be specified like '{o}:variant."
             )
             .into());
+        }
+        (Some(_), false) => return Err(format!("error: {o} is not a sum type.").into()),
+        (Some(v), true) => match ut.variants.iter().find(|n| &n.name == v) {
+            Some(n) => &n.fields,
+            None => return Err(format!("error: {o} doesn't have the variant {v}.").into()),
+        },
+    };
+
+    if let Some(n) = fields
+        .keys()
+        .find(|n| !ut_fields.iter().any(|m| &m.name == *n))
+    {
+        return Err(format!("error: {o} doesn't have the field {n}.").into());
+    }
+
+    for ut in ut_fields.iter() {
+        let field = match (fields.get(&ut.name), &ut.default) {
+            (Some(n), _) => n,
+            (None, Some(n)) => fields.entry(ut.name.clone()).or_insert(Object {
+                private: ut.private,
+                // OPTIMIZE: remove clone.
+                value: n.clone(),
+            }),
+            (None, None) => {
+                return Err(format!(
+                    "error: {} not found in user-type variable definition.",
+                    ut.name
+                )
+                .into());
+            }
         };
         if field.private != ut.private {
             let e = if ut.private { "private" } else { "public" };
             let r = if field.private { "private" } else { "public" };
             return Err(format!("error: {} defined as {e} but specified {r}.", ut.name).into());
         }
-        if ut.ty != field.value.typeid() {
-            return Err(format!(
+        if !field.value.is_of(&ut.ty, &env.ut_map) {
+            return Err(TypeError(format!(
                 "error: field {} expects type {} but {} provided.",
                 ut.name,
                 ut.ty,
-                field.value.typeid()
-            )
+                field.value.typeid_in_detail()
+            ))
             .into());
         }
     }
 
-    Ok(Value::UserType((TypeId::UserDefined(o), fields)))
+    for ut in ut_fields.iter() {
+        field::validate(env, &o, ut, &fields[&ut.name].value)?;
+    }
+
+    let ty = TypeId::UserDefined(o);
+    let n = Value::UserType((ty.clone(), vn, fields));
+    match ut.constructor {
+        Some(Value::Lazy((c, captures))) => construct(env, &ty, c, captures, n),
+        _ => Ok(n),
+    }
+}
+
+/// A function to evaluate the constructor with the value built by `: 'typename` as `##`.
+///
+/// The constructor returns a value of the type, or a `'result` of it
+/// where an error is thrown with the message of the error.
+fn construct(
+    env: &mut Environment,
+    ty: &TypeId,
+    c: std::collections::VecDeque<Token>,
+    captures: Captures,
+    n: Value,
+) -> RResult<Value> {
+    let params = EnterLazyParams {
+        slf: Some(n),
+        args: Some(vec![]),
+        vars: Vec::new(),
+        captures: Some(captures),
+    };
+    let result = eval_block(env, &mut c.into(), params).map(|mut n| n.pop().unwrap_or_default());
+    let n = match catch_propagation(result)? {
+        Value::Result(Ok(n)) => *n,
+        Value::Result(Err(e)) => return Err(format!("error: cannot construct {ty}: {e}").into()),
+        n => n,
+    };
+    if n.typeid() != *ty {
+        return Err(TypeError(format!(
+            "error: the constructor of {ty} must return {ty} but {} returned.",
+            n.typeid_in_detail()
+        ))
+        .into());
+    }
+    Ok(n)
 }
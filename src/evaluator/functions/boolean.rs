@@ -4,11 +4,81 @@ pub fn insert(fm: &mut FunctionMapStack) {
     fm.insert_builtins(
         &TypeId::Bool,
         vec![
-            builtin_fn!("~", vec![], not),
-            builtin_fn!(">>", vec![TypeId::Lazy], on_then),
-            builtin_fn!("!>", vec![TypeId::Lazy], on_else),
-            builtin_fn!("&&", vec![TypeId::Bool], and),
-            builtin_fn!("||", vec![TypeId::Bool], or),
+            builtin_fn!(
+                "~",
+                vec![],
+                not,
+                [
+                    "Returns the negation.",
+                    "",
+                    "```fuzzy",
+                    "T ~  -- ()",
+                    "() ~ -- T",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "&&",
+                vec![TypeId::Bool],
+                and,
+                [
+                    "Returns the logical AND of the subject and the object.",
+                    "",
+                    "```fuzzy",
+                    "T && T   -- T",
+                    "T && ()  -- ()",
+                    "() && T  -- ()",
+                    "() && () -- ()",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "||",
+                vec![TypeId::Bool],
+                or,
+                [
+                    "Returns the logical OR of the subject and the object.",
+                    "",
+                    "```fuzzy",
+                    "T || T   -- T",
+                    "T || ()  -- T",
+                    "() || T  -- T",
+                    "() || () -- ()",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                ">>",
+                vec![TypeId::Lazy],
+                on_then,
+                [
+                    "Evaluates the deferred block object when the subject is `T`.",
+                    "Returns the subject.",
+                    "",
+                    "```fuzzy",
+                    "1 == 1 >> { \"true\" !! } -- outputs true",
+                    "                        -- T",
+                    "0 == 1 >> { \"true\" !! } -- outputs nothing",
+                    "                        -- ()",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "!>",
+                vec![TypeId::Lazy],
+                on_else,
+                [
+                    "Evaluates the deferred block object when the subject is `()`.",
+                    "Returns the subject.",
+                    "",
+                    "```fuzzy",
+                    "1 == 1 !> { \"false\" !! } -- outputs nothing",
+                    "                         -- T",
+                    "0 == 1 !> { \"false\" !! } -- outputs false",
+                    "                         -- ()",
+                    "```"
+                ]
+            ),
         ],
     );
 }
@@ -23,10 +93,12 @@ fn not(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
 
 fn on_then(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     if unwrap_subject(&s) {
-        let o = pop_extract_variant!(args, Lazy);
+        let (o, c) = pop_extract_variant!(args, Lazy);
         let params = EnterLazyParams {
             slf: None,
             args: Some(vec![]),
+            vars: Vec::new(),
+            captures: Some(c),
         };
         let _ = eval_block(env, &mut o.into(), params)?;
     }
@@ -35,10 +107,12 @@ fn on_then(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Val
 
 fn on_else(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     if !unwrap_subject(&s) {
-        let o = pop_extract_variant!(args, Lazy);
+        let (o, c) = pop_extract_variant!(args, Lazy);
         let params = EnterLazyParams {
             slf: None,
             args: Some(vec![]),
+            vars: Vec::new(),
+            captures: Some(c),
         };
         let _ = eval_block(env, &mut o.into(), params)?;
     }
@@ -0,0 +1,261 @@
+use super::{super::variable::Variable, error::Exit, *};
+
+pub fn insert(fm: &mut FunctionMapStack) {
+    fm.insert_builtins(
+        &TypeId::Array,
+        vec![builtin_fn!(
+            "#?",
+            vec![TypeId::String],
+            parse_command_line_args,
+            [
+                "Parses the command line arguments according to the declaration in the subject.",
+                "The object is the program name shown in the usage.",
+                "The subject must be an array that satisfies the following requirements:",
+                "",
+                "- Every declaration consists of a name symbol, a type symbol and an optional description string",
+                "- A name starting with `-` declares an option passed as `--name value` or `--name=value`",
+                "- An option of type `'bool` is a flag and takes no value",
+                "- Other names declare positional arguments in order",
+                "- Types must be `'bool`, numeric types, `'string` or `'symbol`",
+                "",
+                "Each argument is defined as an immutable variable with its name.",
+                "A flag is `T` if passed and `()` otherwise.",
+                "Other options are bound as options, `some[value]` if passed and `none[]` otherwise.",
+                "Passing an option more than once is an error.",
+                "The positional arguments also replace the top-level arguments `#0`, `#1`, ...",
+                "Arguments after `--` are treated as positional.",
+                "If `-h` or `--help` is passed, it outputs the usage and exits with `0`.",
+                "Returns `()`.",
+                "",
+                "```fuzzy",
+                "-- fuzzy greet.fuz world 3 --loud",
+                "['name 'string \"the name to greet\" 'times 'u8 '-loud 'bool '-suffix 'string] #? \"greet\".",
+                "name   -- \"world\"",
+                "#1     -- 3",
+                "loud   -- T",
+                "suffix -- none[]",
+                "```",
+            ]
+        )],
+    );
+}
+
+struct Param {
+    name: String,
+    ty: TypeId,
+    desc: String,
+    option: bool,
+}
+
+fn parse_command_line_args(
+    env: &mut Environment,
+    s: Value,
+    mut args: Vec<Value>,
+) -> RResult<Value> {
+    let s = extract_variant!(s, Array);
+    let o = pop_extract_variant!(args, String);
+    let params = collect_params(&s)?;
+
+    // get raw command line arguments
+    let mut argv = Vec::new();
+    for n in env.args.first().expect("argument stack is empty.") {
+        let Value::String(n) = n else {
+            return Err("error: command line arguments are already parsed.".into());
+        };
+        argv.push(n.clone());
+    }
+
+    // split into positional arguments and options
+    let mut positionals = Vec::new();
+    let mut options = HashMap::new();
+    let mut argv = argv.into_iter();
+    while let Some(n) = argv.next() {
+        if n == "--" {
+            positionals.extend(argv.by_ref());
+            break;
+        }
+        if n == "-h" || n == "--help" {
+            print!("{}", usage(&o, &params));
+            return Err(Exit(0).into());
+        }
+        let Some(n) = n.strip_prefix("--") else {
+            positionals.push(n);
+            continue;
+        };
+        let (k, v) = match n.split_once('=') {
+            Some((k, v)) => (k, Some(v.to_string())),
+            None => (n, None),
+        };
+        let Some(p) = params.iter().find(|p| p.option && p.name == k) else {
+            return Err(format!("error: unknown option --{k}.").into());
+        };
+        if options.contains_key(k) {
+            return Err(format!("error: option --{k} is passed more than once.").into());
+        }
+        let v = match (&p.ty, v) {
+            (TypeId::Bool, None) => Value::Top,
+            (TypeId::Bool, Some(_)) => {
+                return Err(format!("error: option --{k} takes no value.").into());
+            }
+            (_, Some(v)) => parse_param(p, &v)?,
+            (_, None) => {
+                let Some(v) = argv.next() else {
+                    return Err(format!("error: option --{k} requires a value.").into());
+                };
+                parse_param(p, &v)?
+            }
+        };
+        options.insert(k.to_string(), v);
+    }
+
+    // bind
+    let mut values = Vec::new();
+    let mut positionals = positionals.into_iter();
+    for p in params.iter() {
+        let v = if p.option {
+            let v = options.remove(&p.name);
+            match p.ty {
+                TypeId::Bool => v.unwrap_or_default(),
+                _ => Value::Option(v.map(Box::new)),
+            }
+        } else {
+            let Some(v) = positionals.next() else {
+                return Err(format!("error: missing argument <{}>.", p.name).into());
+            };
+            let v = parse_param(p, &v)?;
+            values.push(v.clone());
+            v
+        };
+        let v = Variable {
+            value: v,
+            mutable: false,
+            doc: (!p.desc.is_empty()).then(|| p.desc.clone()),
+        };
+        env.vr_map.insert(p.name.clone(), v)?;
+    }
+    if let Some(n) = positionals.next() {
+        return Err(format!("error: unexpected argument '{n}'.").into());
+    }
+    *env.args.first_mut().expect("argument stack is empty.") = values;
+
+    Ok(Value::Nil)
+}
+
+fn collect_params(s: &[Value]) -> RResult<Vec<Param>> {
+    let mut params = Vec::new();
+    let mut i = 0;
+    while i < s.len() {
+        if i + 1 >= s.len() {
+            return Err("error: argument declaration must have name and type.".into());
+        }
+
+        let Value::Symbol(n) = &s[i] else {
+            return Err("error: argument name must be a symbol.".into());
+        };
+        // NOTE: `--` cannot be written in a symbol because it starts a comment,
+        //       so options are declared with `-` and passed with `--`.
+        let (option, name) = match n.strip_prefix("-") {
+            Some(n) => (true, n.to_string()),
+            None => (false, n.to_string()),
+        };
+        if name.is_empty() || name.starts_with('-') || name == "help" {
+            return Err(format!("error: cannot declare argument '{n}.").into());
+        }
+
+        i += 1;
+
+        let Value::Symbol(t) = &s[i] else {
+            return Err("error: argument type must be a symbol.".into());
+        };
+        let ty = TypeId::from(t);
+        if matches!(
+            ty,
+            TypeId::Any
+                | TypeId::Array
+                | TypeId::ArrayOf(_)
+                | TypeId::Map
+                | TypeId::Lazy
+                | TypeId::Option
+                | TypeId::Result
+                | TypeId::Function(_)
+                | TypeId::UserDefined(_)
+                | TypeId::Var(_)
+        ) {
+            return Err(format!("error: argument {name} cannot be of type {ty}.").into());
+        }
+        if matches!(ty, TypeId::Bool) && !option {
+            return Err(
+                format!("error: positional argument {name} cannot be of type bool.").into(),
+            );
+        }
+
+        i += 1;
+
+        let desc = if let Some(Value::String(d)) = s.get(i) {
+            i += 1;
+            d.clone()
+        } else {
+            String::new()
+        };
+
+        params.push(Param {
+            name,
+            ty,
+            desc,
+            option,
+        });
+    }
+    Ok(params)
+}
+
+fn parse_param(p: &Param, v: &str) -> RResult<Value> {
+    Value::parse(v, &p.ty).ok_or_else(|| {
+        let n = if p.option {
+            format!("--{}", p.name)
+        } else {
+            format!("<{}>", p.name)
+        };
+        format!("error: {n} expects {} but got '{v}'.", p.ty).into()
+    })
+}
+
+fn usage(prog: &str, params: &[Param]) -> String {
+    let mut s = format!("usage: {prog} [options]");
+    let mut args = Vec::new();
+    let mut opts = Vec::new();
+    for p in params {
+        if p.option && matches!(p.ty, TypeId::Bool) {
+            opts.push((format!("--{}", p.name), p.desc.as_str()));
+        } else if p.option {
+            opts.push((format!("--{} <{}>", p.name, p.ty), p.desc.as_str()));
+        } else {
+            s.push_str(&format!(" <{}>", p.name));
+            args.push((
+                format!("<{}>", p.name),
+                format!("{}", p.ty),
+                p.desc.as_str(),
+            ));
+        }
+    }
+    opts.push(("-h, --help".to_string(), "shows this message."));
+    s.push('\n');
+
+    if !args.is_empty() {
+        let w = args.iter().map(|n| n.0.len()).max().unwrap_or(0);
+        let v = args.iter().map(|n| n.1.len()).max().unwrap_or(0);
+        s.push_str("\narguments:\n");
+        for (n, t, d) in args {
+            s.push_str(format!("  {n:w$}  {t:v$}  {d}").trim_end());
+            s.push('\n');
+        }
+    }
+
+    let w = opts.iter().map(|n| n.0.len()).max().unwrap_or(0);
+    s.push_str("\noptions:\n");
+    for (n, d) in opts {
+        s.push_str(format!("  {n:w$}  {d}").trim_end());
+        s.push('\n');
+    }
+
+    s
+}
@@ -1,32 +1,108 @@
 use super::*;
 
 pub fn insert(fm: &mut FunctionMapStack, ty: &TypeId) {
-    fm.insert_builtins(
-        ty,
-        vec![
-            builtin_fn!("==", vec![ty.clone()], equal),
-            builtin_fn!("!=", vec![ty.clone()], not_equal),
-        ],
-    );
-
     // NOTE: some types don't have inequality comparation.
     //       If this language matures, we might consider supporting it
     //       with algorithms similar to those used for JavaScript.
-    if matches!(ty, TypeId::Array | TypeId::Lazy) {
-        return;
+    if !matches!(
+        ty,
+        TypeId::Map | TypeId::Lazy | TypeId::Option | TypeId::Result | TypeId::UserDefined(_)
+    ) {
+        fm.insert_builtins(
+            ty,
+            vec![
+                builtin_fn!(
+                    "<",
+                    vec![ty.clone()],
+                    l,
+                    document(
+                        ty,
+                        "<",
+                        "less than",
+                        [(true, false, "T"), (false, true, "()")]
+                    )
+                ),
+                builtin_fn!(
+                    "<=",
+                    vec![ty.clone()],
+                    le,
+                    document(
+                        ty,
+                        "<=",
+                        "less than or equal to",
+                        [(true, true, "T"), (false, true, "()")]
+                    )
+                ),
+                builtin_fn!(
+                    ">",
+                    vec![ty.clone()],
+                    g,
+                    document(
+                        ty,
+                        ">",
+                        "greater than",
+                        [(true, false, "()"), (false, true, "T")]
+                    )
+                ),
+                builtin_fn!(
+                    ">=",
+                    vec![ty.clone()],
+                    ge,
+                    document(
+                        ty,
+                        ">=",
+                        "greater than or equal to",
+                        [(true, true, "T"), (true, false, "()")]
+                    )
+                ),
+            ],
+        );
     }
 
     fm.insert_builtins(
         ty,
         vec![
-            builtin_fn!("<", vec![ty.clone()], l),
-            builtin_fn!(">", vec![ty.clone()], g),
-            builtin_fn!("<=", vec![ty.clone()], le),
-            builtin_fn!(">=", vec![ty.clone()], ge),
+            builtin_fn!(
+                "==",
+                vec![ty.clone()],
+                equal,
+                document(
+                    ty,
+                    "==",
+                    "equal to",
+                    [(true, true, "T"), (true, false, "()")]
+                )
+            ),
+            builtin_fn!(
+                "!=",
+                vec![ty.clone()],
+                not_equal,
+                document(
+                    ty,
+                    "!=",
+                    "not equal to",
+                    [(true, true, "()"), (true, false, "T")]
+                )
+            ),
         ],
     );
 }
 
+/// A function to generate the document of a comparison function.
+///
+/// * `relation` - How the subject relates to the object when the result is `T`.
+/// * `cases` - Whether each side of an example is the lesser literal, and the result.
+fn document(ty: &TypeId, vn: &str, relation: &str, cases: [(bool, bool, &str); 2]) -> String {
+    let mut s = format!("Checks if the subject is {relation} the object.");
+    if let Some(ex) = Example::of(ty) {
+        let pick = |n: bool| if n { &ex.less } else { &ex.greater };
+        let lines = cases.map(|(n, m, r)| (format!("{} {vn} {}", pick(n), pick(m)), r.to_string()));
+        s.push_str("\n\n");
+        s.push_str(&format_example(&lines));
+    }
+    s
+}
+
 fn equal(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
     let o = pop_object(args);
     if s.equal(&o) {
@@ -47,7 +123,7 @@ fn not_equal(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value>
 
 fn l(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
     let o = pop_object(args);
-    if s.l(&o) {
+    if s.l(&o)? {
         Ok(Value::Top)
     } else {
         Ok(Value::Nil)
@@ -56,7 +132,7 @@ fn l(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
 
 fn g(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
     let o = pop_object(args);
-    if s.g(&o) {
+    if s.g(&o)? {
         Ok(Value::Top)
     } else {
         Ok(Value::Nil)
@@ -65,7 +141,7 @@ fn g(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
 
 fn le(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
     let o = pop_object(args);
-    if s.l(&o) || s.equal(&o) {
+    if s.l(&o)? || s.equal(&o) {
         Ok(Value::Top)
     } else {
         Ok(Value::Nil)
@@ -74,7 +150,7 @@ fn le(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
 
 fn ge(_: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
     let o = pop_object(args);
-    if s.g(&o) || s.equal(&o) {
+    if s.g(&o)? || s.equal(&o) {
         Ok(Value::Top)
     } else {
         Ok(Value::Nil)
@@ -0,0 +1,223 @@
+use super::*;
+
+/// A page of `docs/types`, which documents builtin functions of a type.
+struct Page {
+    file: &'static str,
+    title: &'static str,
+    /// The type whose builtin functions are listed.
+    ty: TypeId,
+    /// Whether the page covers several types like `ty`.
+    /// If so, arguments of the subject type are shown as `SAME-AS-SUBJECT`.
+    generic: bool,
+    general: &'static [&'static str],
+}
+
+impl Page {
+    fn format_types(&self, vn: &str, types: &[TypeId]) -> String {
+        if matches!(self.ty, TypeId::Function(_)) && vn == "@" {
+            return "ARGUMENT1 ARGUMENT2 ...".to_string();
+        }
+        types
+            .iter()
+            .map(|n| {
+                if self.generic && n == &self.ty {
+                    "SAME-AS-SUBJECT".to_string()
+                } else {
+                    quote(n)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn render(&self, fm: &FunctionMapStack) -> String {
+        let mut s = format!(
+            "# {}\n\n## General\n\n{}\n\n## Functions\n",
+            self.title,
+            self.general.join("\n")
+        );
+        for vn in fm.get_builtin_names(&self.ty) {
+            let f = fm
+                .get_builtin(&self.ty, vn)
+                .expect("builtin function not found.");
+            s.push_str(&format!(
+                "\n### `{vn}`: `@[{}]`\n",
+                self.format_types(vn, &f.types)
+            ));
+            if let Some(n) = &f.doc {
+                s.push_str(&format!("\n{n}\n"));
+            }
+        }
+        s
+    }
+}
+
+/// A function to format a type as it's written in type lists.
+fn quote(ty: &TypeId) -> String {
+    match ty {
+        TypeId::Function(n) => {
+            format!("[{}]", n.iter().map(quote).collect::<Vec<_>>().join(" "))
+        }
+        n => format!("'{n}"),
+    }
+}
+
+/// A function to generate the pages of `docs/types` from the documents of builtin functions.
+///
+/// Returns pairs of a file name and its content.
+pub fn builtin_pages() -> Vec<(&'static str, String)> {
+    let mut fm = FunctionMapStack::default();
+    fm.push();
+    lazy::insert_function_type(&mut fm, Vec::new());
+
+    let pages = [
+        Page {
+            file: "ARRAY.md",
+            title: "[]",
+            ty: TypeId::Array,
+            generic: false,
+            general: &[
+                "A type representing an array.",
+                "Created using array blocks.",
+                "Arrays can contain values of any type.",
+                "",
+                "In type lists, `'[]` accepts any array,",
+                "and `'[type]` (e.g. `'[i32]`, `'[[string]]`) accepts arrays whose elements are all of the type.",
+                "The REPL shows the type of an array whose elements are all of the same type.",
+            ],
+        },
+        Page {
+            file: "BOOL.md",
+            title: "bool",
+            ty: TypeId::Bool,
+            generic: false,
+            general: &[
+                "A type representing boolean values. It has only the following two values:",
+                "",
+                "| Notation | Meaning |",
+                "| --- | --- |",
+                "| `T` | True |",
+                "| `()` | False |",
+            ],
+        },
+        Page {
+            file: "FUNCTION.md",
+            title: "function",
+            ty: TypeId::Function(vec![]),
+            generic: false,
+            general: &[
+                "A function type is represented by an array listing `'symbol` values or function types that represent argument types.",
+                "In the REPL and this documentation, it is displayed as `@[argument1 argument2 ...]`.",
+            ],
+        },
+        Page {
+            file: "LAZY.md",
+            title: "\\{\\}",
+            ty: TypeId::Lazy,
+            generic: false,
+            general: &[
+                "A type representing a deferred evaluation block.",
+                "Can be viewed as a collection of strings.",
+            ],
+        },
+        Page {
+            file: "MAP.md",
+            title: "#[]",
+            ty: TypeId::Map,
+            generic: false,
+            general: &[
+                "A type representing a map, which associates keys with values.",
+                "Created using map blocks, in which keys and values are placed alternately.",
+                "Keys must be `'string`, `'symbol` or integers, and keys of different types are distinguished.",
+                "Pairs are kept in the order of insertion.",
+            ],
+        },
+        Page {
+            file: "NUMERIC.md",
+            title: "numeric",
+            ty: TypeId::I32,
+            generic: true,
+            general: &[
+                "Numeric types include the following 3 categories with 12 types in total:",
+                "",
+                "| Category | Types |",
+                "| --- | --- |",
+                "| Signed integers | `'i8`, `'i16`, `'i32`, `'i64`, `'i128` |",
+                "| Unsigned integers | `'u8`, `'u16`, `'u32`, `'u64`, `'u128` |",
+                "| Floating-point numbers | `'f32`, `'f64` |",
+            ],
+        },
+        Page {
+            file: "OPTION.md",
+            title: "option",
+            ty: TypeId::Option,
+            generic: false,
+            general: &[
+                "A type representing a value that may be absent.",
+                "An option that has a value is created by `?+` on any value, and is displayed as `some[value]`.",
+                "An option that has no value is created by `() ?-`, and is displayed as `none[]`.",
+                "",
+                "Unlike `()`, which may be a stored value, an absent value is distinguished from any value.",
+                "Non-throwing variants of builtins such as `@?` on arrays return options.",
+            ],
+        },
+        Page {
+            file: "RESULT.md",
+            title: "result",
+            ty: TypeId::Result,
+            generic: false,
+            general: &[
+                "A type representing a success or an error.",
+                "A success is created by `!+` on any value, and is displayed as `ok[value]`.",
+                "An error is created by `!-` on any value, and is displayed as `err[value]`.",
+                "",
+                "Non-throwing variants of builtins such as `:?` return results,",
+                "where the value of an error is the error message.",
+                "`?^` returns an error from the current function immediately.",
+            ],
+        },
+        Page {
+            file: "STRING.md",
+            title: "string",
+            ty: TypeId::String,
+            generic: false,
+            general: &[
+                "A type representing strings.",
+                "A string literal can span multiple lines.",
+                "Supports the following escape characters:",
+                "",
+                "| Notation | Meaning |",
+                "| --- | --- |",
+                "| `\\\\` | \\\\ |",
+                "| `\\\"` | \" |",
+                "| `\\r` | CR |",
+                "| `\\n` | LF |",
+                "| `\\t` | Tab character |",
+                "| `\\0` | Null character |",
+                "| `\\xNN` | ASCII character of the hexadecimal code `00` to `7F` |",
+                "| `\\u{NNNN}` | Unicode character of the hexadecimal code point |",
+                "",
+                "Any other escape sequence is a lexical error.",
+                "",
+                "A raw string literal starts with `r\"` and ends with `\"`, and escape sequences aren't processed in it.",
+                "To contain `\"`, put the same number of `#` between `r` and `\"` and after the closing `\"`.",
+                "",
+                "```fuzzy",
+                "\"line 1",
+                "line 2\"             -- \"line 1\\nline 2\"",
+                "\"\\u{1F600}\\x41\"     -- \"😀A\"",
+                "r\"C:\\path\\n\"        -- \"C:\\\\path\\\\n\"",
+                "r#\"say \"hello\"\"#    -- \"say \\\"hello\\\"\"",
+                "```",
+            ],
+        },
+        Page {
+            file: "SYMBOL.md",
+            title: "symbol",
+            ty: TypeId::Symbol,
+            generic: false,
+            general: &["A symbol type.", "An identifier that starts with `'`."],
+        },
+    ];
+    pages.iter().map(|n| (n.file, n.render(&fm))).collect()
+}
@@ -0,0 +1,143 @@
+use super::{super::usertype::UserTypeField, error::TypeError, value::Object, *};
+
+pub fn insert(fm: &mut FunctionMapStack, ty: &TypeId) {
+    fm.insert_builtins(
+        ty,
+        vec![
+            builtin_fn!(
+                ":<",
+                vec![TypeId::Symbol, TypeId::Any],
+                with_field,
+                [
+                    "Returns a copy of the subject with the field set to object2.",
+                    "Object1 is the field name with the visibility prefix `:` or `::`.",
+                    "A private field can be set only in functions of the type.",
+                    "Object2 must be of the type of the field.",
+                    "",
+                    "```fuzzy",
+                    "[':x 'i32 ':y 'i32] |> 'point.",
+                    "[':x 1 ':y 2] : 'point, -> 'p.",
+                    "p :< ':x 5  -- [:x 5 :y 2]",
+                    "p :< ':x \"\" -- error",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                ":<<",
+                vec![TypeId::Array],
+                with_fields,
+                [
+                    "Returns a copy of the subject with the fields set to the values in the object.",
+                    "The object is an array of field names and values placed alternately like `: 'typename`,",
+                    "but fields not listed keep their values.",
+                    "",
+                    "```fuzzy",
+                    "[':x 'i32 ':y 'i32] |> 'point.",
+                    "[':x 1 ':y 2] : 'point, -> 'p.",
+                    "p :<< [':x 5 ':y 6] -- [:x 5 :y 6]",
+                    "```",
+                ]
+            ),
+        ],
+    );
+}
+
+/// A function to set the field of a user-defined type value, checking its visibility and type.
+///
+/// * `name` - The field name with the visibility prefix `:` or `::`.
+pub fn set(env: &mut Environment, value: &mut Value, name: &str, v: Value) -> RResult<()> {
+    let Value::UserType((ty, vn, fields)) = value else {
+        return Err(format!("error: {} has no field.", value.typeid()).into());
+    };
+    let (private, name) = if let Some(n) = name.strip_prefix("::") {
+        (true, n)
+    } else if let Some(n) = name.strip_prefix(':') {
+        (false, n)
+    } else {
+        return Err("error: field name must start with ':' or '::'.".into());
+    };
+
+    let TypeId::UserDefined(tn) = &ty else {
+        panic!("type missmatched.");
+    };
+    let Some(ut) = env.ut_map.get(tn) else {
+        return Err(format!("error: the type {tn} not defined.").into());
+    };
+    let declared = match vn {
+        Some(vn) => ut
+            .variants
+            .iter()
+            .find(|n| &n.name == vn)
+            .map(|n| n.fields.as_slice())
+            .unwrap_or_default(),
+        None => ut.fields.as_slice(),
+    };
+    let Some(field) = declared.iter().find(|n| n.name == name) else {
+        return Err(format!("error: {tn} doesn't have the field {name}.").into());
+    };
+    // OPTIMIZE: remove clone.
+    let field = field.clone();
+
+    if private != field.private {
+        let e = if field.private { "private" } else { "public" };
+        let r = if private { "private" } else { "public" };
+        return Err(format!("error: {name} of {tn} defined as {e} but specified {r}.").into());
+    }
+    if private && env.get_self_type().as_ref() != Some(ty) {
+        return Err(format!("error: {name} of {tn} is private.").into());
+    }
+    if !v.is_of(&field.ty, &env.ut_map) {
+        return Err(TypeError(format!(
+            "error: field {name} expects type {} but {} provided.",
+            field.ty,
+            v.typeid_in_detail()
+        ))
+        .into());
+    }
+
+    validate(env, tn, &field, &v)?;
+
+    fields.insert(name.to_string(), Object { private, value: v });
+    Ok(())
+}
+
+/// A function to check the value of the field with its validator.
+///
+/// * `tn` - The name of the type, which is shown in the error.
+pub fn validate(env: &mut Environment, tn: &str, field: &UserTypeField, v: &Value) -> RResult<()> {
+    let Some(f) = &field.validator else {
+        return Ok(());
+    };
+    // OPTIMIZE: remove clone.
+    if lazy::call_function(env, f.clone(), vec![v.clone()])? == Value::Nil {
+        return Err(format!(
+            "error: {} is invalid for field {} of {tn}.",
+            v.format_in_detail(env),
+            field.name
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn with_field(env: &mut Environment, mut s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let name = pop_extract_variant!(args, Symbol);
+    let v = args.pop().expect("type missmatched.");
+    set(env, &mut s, &name, v)?;
+    Ok(s)
+}
+
+fn with_fields(env: &mut Environment, mut s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let o = pop_extract_variant!(args, Array);
+    if !o.len().is_multiple_of(2) {
+        return Err("error: field definition must have both name and value.".into());
+    }
+    let mut o = o.into_iter();
+    while let (Some(name), Some(v)) = (o.next(), o.next()) {
+        let Value::Symbol(name) = name else {
+            return Err("error: field name must be a symbol.".into());
+        };
+        set(env, &mut s, &name, v)?;
+    }
+    Ok(s)
+}
@@ -1,46 +1,254 @@
-use super::*;
+use super::{error::TypeError, *};
 
 pub fn insert(fm: &mut FunctionMapStack) {
     fm.insert_builtins(
         &TypeId::Lazy,
         vec![
-            builtin_fn!("#", vec![], length),
-            builtin_fn!("^", vec![], first),
-            builtin_fn!("$", vec![], last),
-            builtin_fn!("@", vec![TypeId::I32], at),
-            builtin_fn!("@@", vec![TypeId::I32, TypeId::String], replace),
-            builtin_fn!("@<", vec![TypeId::I32, TypeId::String], ins),
-            builtin_fn!("@-", vec![TypeId::I32], remove),
-            builtin_fn!("$>", vec![TypeId::String], push),
-            builtin_fn!("$-", vec![], pop),
-            builtin_fn!("%", vec![], eval_lazy_block),
-            builtin_fn!("%%", vec![TypeId::Lazy], while_loop),
-            builtin_fn!(":", vec![TypeId::Array], define_function),
+            builtin_fn!(
+                "#",
+                vec![],
+                length,
+                [
+                    "Returns the length of the deferred block.",
+                    "The return type is `'u32`.",
+                    "",
+                    "```fuzzy",
+                    "{} #        -- 0",
+                    "{ 1 + 2 } # -- 3",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "^",
+                vec![],
+                first,
+                [
+                    "Returns the first element.",
+                    "The return type is `'string`.",
+                    "Returns `()` if the deferred block is empty.",
+                    "",
+                    "```fuzzy",
+                    "{} ^        -- ()",
+                    "{ 1 + 2 } ^ -- \"1\"",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "$",
+                vec![],
+                last,
+                [
+                    "Returns the last element.",
+                    "The return type is `'string`.",
+                    "Returns `()` if the block is empty.",
+                    "",
+                    "```fuzzy",
+                    "{} $        -- ()",
+                    "{ 1 + 2 } $ -- \"2\"",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@",
+                vec![TypeId::I32],
+                at,
+                [
+                    "Returns the element at the specified index.",
+                    "The return type is `'string`.",
+                    "Returns `()` if the index is out of bounds.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 } $ 1   -- \"+\"",
+                    "{ 1 + 2 } $ -1  -- \"2\"",
+                    "{ 1 + 2 } @ 100 -- ()",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@?",
+                vec![TypeId::I32],
+                try_at,
+                [
+                    "Returns the element at the specified index as an option.",
+                    "Returns `none[]` if the index is out of bounds.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 } @? 1   -- some[+]",
+                    "{ 1 + 2 } @? 100 -- none[]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@@",
+                vec![TypeId::I32, TypeId::String],
+                replace,
+                [
+                    "Replaces the element at the specified index with the object.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 } @@ 1 \"-\" -- { 1 - 2 }",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@<",
+                vec![TypeId::I32, TypeId::String],
+                ins,
+                [
+                    "Inserts an element at the specified index.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 2 } @@ 1 \"+\" -- { 1 + 2 }",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@-",
+                vec![TypeId::I32],
+                remove,
+                [
+                    "Removes the element at the specified index.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 } @@ 1 -- { 1 2 }",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "$-",
+                vec![],
+                pop,
+                [
+                    "Removes the last element.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 } $- -- { 1 + }",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "$>",
+                vec![TypeId::String],
+                push,
+                [
+                    "Appends an element to the end.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + } $> \"2\" -- { 1 + 2 }",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@:",
+                slice_types(),
+                slice,
+                [
+                    "Returns a deferred block of the elements from the start index to before the end index.",
+                    "Negative indices count from the end, and the end index can be omitted to take the rest.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 * 3 } @: 2    -- { 2 * 3 }",
+                    "{ 1 + 2 * 3 } @: 0 -2 -- { 1 + 2 }",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%",
+                vec![],
+                eval_lazy_block,
+                [
+                    "Evaluates the deferred block.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 } % -- 3",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "%%",
+                vec![TypeId::Lazy],
+                while_loop,
+                [
+                    "Repeatedly evaluates the object until the subject returns `()`.",
+                    "",
+                    "```fuzzy",
+                    "5 -> 'i.",
+                    "{ i > 0 } %% {",
+                    "  i !",
+                    "  i - 1, -> 'i.",
+                    "}",
+                    "-- outputs 54321",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                ":",
+                vec![TypeId::Array],
+                define_function,
+                [
+                    "Casts the subject to the function type indicated by the object.",
+                    "The object must be an array that satisfies the following requirements:",
+                    "",
+                    "- All leaf elements must be of type `'symbol`",
+                    "- Each element represents an argument type",
+                    "- If an element is an array, it represents a function type",
+                    "- A type name starting with `$` like `'$T` is a type variable",
+                    "- A map like `#['i32 0]` is an optional parameter with its default value,",
+                    "  which must follow the required ones",
+                    "- A type name followed by `*` like `'i32*` is a variadic parameter,",
+                    "  which must be the last and collects the rest of the arguments into an array",
+                    "",
+                    "Arguments for optional and variadic parameters are collected until `.`, `,` or `;`.",
+                    "",
+                    "A type variable is bound to the type of the first argument in its place on each call,",
+                    "and the other arguments in its places must be of the bound type.",
+                    "",
+                    "A function sees the variables visible where its block was written, not where it is called.",
+                    "Assigning a captured variable with `->` updates it for everyone who captured it,",
+                    "and captured variables defined with `=>` can't be assigned.",
+                    "`##` is not captured and refers to the subject of the function being executed.",
+                    "",
+                    "```fuzzy",
+                    "{ #0 @ #1 #2 } : [['i32 'i32] 'i32 'i32] -- a function that takes a function with 2 'i32 arguments and 2 'i32 arguments",
+                    "{ #1 @ (#0 ^) } : ['[$T] ['$T]], -> 'apply-head.",
+                    "apply-head @ [3 4] { #0 * 2 } : ['i32]   -- 6",
+                    "apply-head @ [3 4] { #0 } : ['string]    -- error because $T is bound to i32",
+                    "{ #0 * #1 } : ['i32 #['i32 2]], -> 'scale.",
+                    "scale @ 3, !!                            -- 6",
+                    "scale @ 3 3, !!                          -- 9",
+                    "{ #0 ! #1 !! } : ['string '_*], -> 'log.",
+                    "log @ \"values: \" 1 2 3.                  -- values: [1 2 3]",
+                    "{ 0 -> 'n. { n + 1, -> 'n. n } : [] } : [], -> 'make-counter.",
+                    "make-counter @, -> 'c.",
+                    "c @. c @                                 -- 2",
+                    "```",
+                ]
+            ),
         ],
     );
 }
 
 fn length(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
-    let s = extract_variant!(s, Lazy);
+    let (s, _) = extract_variant!(s, Lazy);
     Ok(Value::U32(s.len() as u32))
 }
 
 fn first(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
-    let s = extract_variant!(s, Lazy);
+    let (s, _) = extract_variant!(s, Lazy);
     Ok(s.back()
         .map(|n| Value::String(n.to_string()))
         .unwrap_or_default())
 }
 
 fn last(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
-    let s = extract_variant!(s, Lazy);
+    let (s, _) = extract_variant!(s, Lazy);
     Ok(s.front()
         .map(|n| Value::String(n.to_string()))
         .unwrap_or_default())
 }
 
 fn at(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let s = extract_variant!(s, Lazy);
+    let (s, _) = extract_variant!(s, Lazy);
     let o = pop_extract_variant!(args, I32);
     let Ok(i) = convert_index(o, s.len()) else {
         return Ok(Value::Nil);
@@ -49,97 +257,348 @@ fn at(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     Ok(Value::String(s[i].to_string()))
 }
 
+fn try_at(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let (s, _) = extract_variant!(s, Lazy);
+    let o = pop_extract_variant!(args, I32);
+    let n = convert_index(o, s.len())
+        .ok()
+        .map(|i| Box::new(Value::String(s[s.len() - 1 - i].to_string())));
+    Ok(Value::Option(n))
+}
+
 fn replace(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let mut s = extract_variant!(s, Lazy);
+    let (mut s, c) = extract_variant!(s, Lazy);
     let o = pop_extract_variant!(args, I32);
     let n = pop_extract_variant!(args, String);
     let i = convert_index(o, s.len())?;
     let i = s.len() - 1 - i;
     s[i] = Token::from(&n);
-    Ok(Value::Lazy(s))
+    Ok(Value::Lazy((s, c)))
 }
 
 fn ins(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let mut s = extract_variant!(s, Lazy);
+    let (mut s, c) = extract_variant!(s, Lazy);
     let o = pop_extract_variant!(args, I32);
     let n = pop_extract_variant!(args, String);
     let i = convert_index(o, s.len())?;
     let i = s.len() - 1 - i;
     s.insert(i, Token::from(&n));
-    Ok(Value::Lazy(s))
+    Ok(Value::Lazy((s, c)))
 }
 
 fn remove(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let mut s = extract_variant!(s, Lazy);
+    let (mut s, c) = extract_variant!(s, Lazy);
     let o = pop_extract_variant!(args, I32);
     let i = convert_index(o, s.len())?;
     let i = s.len() - 1 - i;
     s.remove(i);
-    Ok(Value::Lazy(s))
+    Ok(Value::Lazy((s, c)))
 }
 
 fn push(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let mut s = extract_variant!(s, Lazy);
+    let (mut s, c) = extract_variant!(s, Lazy);
     let o = pop_extract_variant!(args, String);
     s.push_front(Token::from(&o));
-    Ok(Value::Lazy(s))
+    Ok(Value::Lazy((s, c)))
 }
 
 fn pop(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
-    let mut s = extract_variant!(s, Lazy);
+    let (mut s, c) = extract_variant!(s, Lazy);
     s.pop_front();
-    Ok(Value::Lazy(s))
+    Ok(Value::Lazy((s, c)))
+}
+
+fn slice(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let (s, c) = extract_variant!(s, Lazy);
+    let start = pop_extract_variant!(args, I32);
+    let end = args.pop().map(|n| extract_variant!(n, I32));
+    let r = convert_range(start, end, s.len())?;
+    // NOTE: The elements are stored in reverse order.
+    let r = s.len() - r.end..s.len() - r.start;
+    Ok(Value::Lazy((s.range(r).cloned().collect(), c)))
 }
 
 fn eval_lazy_block(env: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
-    let s = extract_variant!(s, Lazy);
-    eval(env, &mut s.into(), Vec::new())
+    let (s, c) = extract_variant!(s, Lazy);
+    eval(env, &mut s.into(), c, Vec::new())
 }
 
 fn while_loop(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let s = extract_variant!(s, Lazy);
-    let o = pop_extract_variant!(args, Lazy);
+    let (s, c) = extract_variant!(s, Lazy);
+    let (o, d) = pop_extract_variant!(args, Lazy);
     loop {
-        let r = eval(env, &mut s.clone().into(), Vec::new())?;
+        let r = eval(env, &mut s.clone().into(), c.clone(), Vec::new())?;
         if r == Value::Nil {
             break;
         }
-        eval(env, &mut o.clone().into(), vec![r])?;
+        eval(env, &mut o.clone().into(), d.clone(), vec![r])?;
     }
     Ok(Value::Nil)
 }
 
 fn define_function(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let s = extract_variant!(s, Lazy);
+    let (s, c) = extract_variant!(s, Lazy);
     let o = pop_extract_variant!(args, Array);
 
-    let ts = convert_symbols_to_typeids(&o)?;
+    let (ts, defaults) = convert_params(&o)?;
+    let optionals = ts.iter().filter_map(|n| match n {
+        TypeId::Optional(n) => Some(n),
+        _ => None,
+    });
+    for (t, d) in optionals.zip(defaults.iter()) {
+        if !d.is_of(t, &env.ut_map) {
+            return Err(TypeError(format!(
+                "error: the default value must be {t} but got {}.",
+                d.typeid_in_detail()
+            ))
+            .into());
+        }
+    }
     let t = TypeId::Function(ts.clone());
 
     if !env.fn_map.is_defined(None, &t, "@") {
-        let n = Function {
-            mutable: false,
-            private: false,
-            types: ts,
-            code: FunctionCode::Builtin(call),
-        };
-        env.fn_map.insert_builtins(&t, vec![("@".to_string(), n)]);
-        variable::insert(&mut env.fn_map, &t);
+        insert_function_type(&mut env.fn_map, ts);
     }
 
-    Ok(Value::Function((t, s.into())))
+    Ok(Value::Function((t, s.into(), c, defaults)))
+}
+
+pub fn insert_function_type(fm: &mut FunctionMapStack, ts: Vec<TypeId>) {
+    let t = TypeId::Function(ts.clone());
+    print::insert(fm, &t);
+    variable::insert(fm, &t);
+    option::insert_constructors(fm, &t);
+    pattern::insert(fm, &t);
+    fm.insert_builtins(
+        &t,
+        vec![
+            builtin_fn!(
+                "@",
+                ts,
+                call,
+                [
+                    "Evaluates the function with the objects as arguments.",
+                    "",
+                    "```fuzzy",
+                    "{ 1 + 2 } : [], -> 'f.",
+                    "f @                               -- 3",
+                    "{ #0 + #1 } : ['i32 'i32], -> 'f.",
+                    "f @ 1 2                           -- 3",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "@<",
+                vec![TypeId::Array],
+                partial,
+                [
+                    "Returns a function that calls the subject with the elements of the object as the first arguments",
+                    "followed by its own arguments.",
+                    "The returned function takes the rest of the arguments of the subject.",
+                    "",
+                    "```fuzzy",
+                    "{ #0 + #1 } : ['i32 'i32], -> 'add.",
+                    "add @< [1], -> 'incr.",
+                    "incr @ 2       -- 3",
+                    "add @< [\"a\"]   -- error because #0 must be i32",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "@>",
+                vec![TypeId::Any],
+                compose,
+                [
+                    "Returns a function that calls the subject with its arguments",
+                    "and then calls the object with the result.",
+                    "The object must be a function that takes 1 argument.",
+                    "The returned function takes the same arguments as the subject.",
+                    "",
+                    "```fuzzy",
+                    "{ #0 + #1 } : ['i32 'i32], -> 'add.",
+                    "{ #0 * 2 } : ['i32], -> 'double.",
+                    "add @> double, -> 'f.",
+                    "f @ 1 2 -- 6",
+                    "```"
+                ]
+            ),
+        ],
+    );
 }
 
 fn call(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
-    let mut s = extract_variant!(s, Function);
+    let (t, mut tokens, c, defaults) = extract_variant!(s, Function);
+    let TypeId::Function(ts) = t else {
+        unreachable!()
+    };
     args.reverse();
-    eval(env, &mut s.1, args)
+    let args = fill_args(&ts, &defaults, args);
+    catch_propagation(eval(env, &mut tokens, c, args))
+}
+
+fn partial(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let (t, _, _, defaults) = extract_variant!(s.clone(), Function);
+    let o = pop_extract_variant!(args, Array);
+    let TypeId::Function(ts) = t else {
+        unreachable!()
+    };
+    if let Some(TypeId::Variadic(_)) = ts.last() {
+        return Err(TypeError(
+            "error: a function with a variadic parameter cannot be applied partially.".to_string(),
+        )
+        .into());
+    }
+    if o.len() > ts.len() {
+        return Err(TypeError(format!(
+            "error: the function takes {} arguments but {} passed.",
+            ts.len(),
+            o.len()
+        ))
+        .into());
+    }
+
+    let mut bindings = TypeBindings::new();
+    for (i, n) in o.iter().enumerate() {
+        let t = param_type(&ts, i).unwrap();
+        if !n.unify(t, &mut bindings, &env.ut_map) {
+            return Err(TypeError(format!(
+                "error: the function expects {} for #{i} but got {}.",
+                t.format_instantiated(&bindings),
+                n.typeid_in_detail()
+            ))
+            .into());
+        }
+    }
+    let rest = ts[o.len()..]
+        .iter()
+        .map(|n| n.substitute(&bindings))
+        .collect::<Vec<_>>();
+    let applied = ts[..o.len()]
+        .iter()
+        .filter(|n| matches!(n, TypeId::Optional(_)))
+        .count();
+    let defaults = defaults[applied..].to_vec();
+
+    // NOTE: The returned function evaluates `f @ x0 x1 ... #0 #1 ...`,
+    //       where `f` and `xN` are bound in a frame only visible from it.
+    let mut code = vec![Token::Label("f".to_string()), Token::Label("@".to_string())];
+    let mut vars = vec![("f".to_string(), s)];
+    for (i, n) in o.into_iter().enumerate() {
+        let name = format!("x{i}");
+        code.push(Token::Label(name.clone()));
+        vars.push((name, n));
+    }
+    code.extend((0..rest.len()).map(Token::Argument));
+    Ok(new_function(env, rest, code, vars, defaults))
+}
+
+fn compose(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let (t, _, _, defaults) = extract_variant!(s.clone(), Function);
+    let o = args.pop().unwrap();
+    let TypeId::Function(ts) = t else {
+        unreachable!()
+    };
+    if let Some(TypeId::Variadic(_)) = ts.last() {
+        return Err(TypeError(
+            "error: a function with a variadic parameter cannot be composed.".to_string(),
+        )
+        .into());
+    }
+    match o.typeid() {
+        TypeId::Function(n) if accepts(&n, 1) => (),
+        n @ TypeId::Function(_) => {
+            return Err(TypeError(format!(
+                "error: the object of @> must take 1 argument but it's {n}."
+            ))
+            .into());
+        }
+        n => return Err(TypeError(format!("error: a function expected but got {n}.")).into()),
+    }
+
+    // NOTE: The returned function evaluates `g @ (f @ #0 #1 ...)`,
+    //       where `f` and `g` are bound in a frame only visible from it.
+    let mut code = vec![
+        Token::Label("g".to_string()),
+        Token::Label("@".to_string()),
+        Token::LParen,
+        Token::Label("f".to_string()),
+        Token::Label("@".to_string()),
+    ];
+    code.extend((0..ts.len()).map(Token::Argument));
+    code.push(Token::RParen);
+    let vars = vec![("f".to_string(), s), ("g".to_string(), o)];
+    Ok(new_function(env, ts, code, vars, defaults))
+}
+
+/// A function to create a function value from code in order,
+/// which sees only the variables given.
+fn new_function(
+    env: &mut Environment,
+    ts: Vec<TypeId>,
+    mut code: Vec<Token>,
+    vars: Vec<(String, Value)>,
+    defaults: Vec<Value>,
+) -> Value {
+    let t = TypeId::Function(ts.clone());
+    if !env.fn_map.is_defined(None, &t, "@") {
+        insert_function_type(&mut env.fn_map, ts);
+    }
+    code.reverse();
+    Value::Function((t, code, Captures::with(vars), defaults))
+}
+
+/// A function to check if the function type can be called with `n` arguments.
+fn accepts(types: &[TypeId], n: usize) -> bool {
+    let (min, max) = arity(types);
+    min <= n && max.is_none_or(|m| n <= m)
+}
+
+/// A function to call a function value passed to a builtin, such as the object of `?@`.
+///
+/// * `args` - The arguments in order, checked against the argument types of the function.
+pub fn call_function(env: &mut Environment, f: Value, args: Vec<Value>) -> RResult<Value> {
+    let Value::Function((TypeId::Function(ts), mut tokens, c, defaults)) = f else {
+        return Err(TypeError(format!(
+            "error: a function expected but got {}.",
+            f.typeid()
+        ))
+        .into());
+    };
+    if !accepts(&ts, args.len()) {
+        return Err(TypeError(format!(
+            "error: the function {} cannot take {} arguments.",
+            TypeId::Function(ts),
+            args.len()
+        ))
+        .into());
+    }
+    let mut bindings = TypeBindings::new();
+    for (i, n) in args.iter().enumerate() {
+        let t = param_type(&ts, i).unwrap();
+        if !n.unify(t, &mut bindings, &env.ut_map) {
+            return Err(TypeError(format!(
+                "error: the function expects {} for #{i} but got {}.",
+                t.format_instantiated(&bindings),
+                n.typeid_in_detail()
+            ))
+            .into());
+        }
+    }
+    let args = fill_args(&ts, &defaults, args);
+    catch_propagation(eval(env, &mut tokens, c, args))
 }
 
-fn eval(env: &mut Environment, tokens: &mut Vec<Token>, args: Vec<Value>) -> RResult<Value> {
+fn eval(
+    env: &mut Environment,
+    tokens: &mut Vec<Token>,
+    captures: Captures,
+    args: Vec<Value>,
+) -> RResult<Value> {
     let params = EnterLazyParams {
         slf: None,
         args: Some(args),
+        vars: Vec::new(),
+        captures: Some(captures),
     };
     let result = eval_block(env, tokens, params)?.pop().unwrap_or_default();
     Ok(result)
@@ -0,0 +1,220 @@
+use super::*;
+
+pub fn insert(fm: &mut FunctionMapStack) {
+    fm.insert_builtins(
+        &TypeId::Map,
+        vec![
+            builtin_fn!(
+                "#",
+                vec![],
+                length,
+                [
+                    "Returns the number of pairs in the map.",
+                    "The return type is `'u32`.",
+                    "",
+                    "```fuzzy",
+                    "#[] #          -- 0",
+                    "#['a 1 'b 2] # -- 2",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@",
+                vec![TypeId::Any],
+                get,
+                [
+                    "Returns the value of the specified key.",
+                    "Throws an error if the key is not found.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1 \"b\" 2] @ 'a  -- 1",
+                    "#['a 1 \"b\" 2] @ \"b\" -- 2",
+                    "#['a 1 \"b\" 2] @ 'c  -- error",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@<",
+                vec![TypeId::Any, TypeId::Any],
+                ins,
+                [
+                    "Inserts a pair of the key and the value.",
+                    "If the key already exists, its value is replaced.",
+                    "The key must be a `'string`, a `'symbol` or an integer.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1] @< 'b 2 -- #[a 1 b 2]",
+                    "#['a 1] @< 'a 2 -- #[a 2]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@-",
+                vec![TypeId::Any],
+                remove,
+                [
+                    "Removes the pair of the specified key.",
+                    "Does nothing if the key is not found.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1 'b 2] @- 'a -- #[b 2]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@?",
+                vec![TypeId::Any],
+                try_get,
+                [
+                    "Returns the value of the specified key as an option.",
+                    "Returns `none[]` if the key is not found.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1 'b ()] @? 'a -- some[1]",
+                    "#['a 1 'b ()] @? 'b -- some[()]",
+                    "#['a 1 'b ()] @? 'c -- none[]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "^?",
+                vec![TypeId::Any],
+                contains_key,
+                [
+                    "Checks if the map contains the specified key.",
+                    "Keys of different types are distinguished.",
+                    "",
+                    "```fuzzy",
+                    "#[1 'a] ^? 1   -- T",
+                    "#[1 'a] ^? 1u8 -- ()",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "^^",
+                vec![],
+                keys,
+                [
+                    "Returns the keys as an array in the order of insertion.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1 'b 2] ^^ -- [a b]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "$$",
+                vec![],
+                values,
+                [
+                    "Returns the values as an array in the order of insertion.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1 'b 2] $$ -- [1 2]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "^$",
+                vec![],
+                entries,
+                [
+                    "Returns the pairs as an array of `[key value]` arrays in the order of insertion.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1 'b 2] ^$ -- [[a 1] [b 2]]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "++",
+                vec![TypeId::Map],
+                merge,
+                [
+                    "Merges the object into the subject.",
+                    "The values of the object take precedence over the ones of the subject.",
+                    "",
+                    "```fuzzy",
+                    "#['a 1 'b 2] ++ #['b 3 'c 4] -- #[a 1 b 3 c 4]",
+                    "```",
+                ]
+            ),
+        ],
+    );
+}
+
+fn length(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Map);
+    Ok(Value::U32(s.len() as u32))
+}
+
+fn get(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Map);
+    let o = args.pop().expect("type missmatched.");
+    let Some(i) = Value::map_position(&s, &o) else {
+        return Err(format!("error: key {o} not found.").into());
+    };
+    Ok(s.swap_remove(i).1)
+}
+
+fn try_get(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Map);
+    let o = args.pop().expect("type missmatched.");
+    let n = Value::map_position(&s, &o).map(|i| Box::new(s.swap_remove(i).1));
+    Ok(Value::Option(n))
+}
+
+fn ins(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Map);
+    let k = args.pop().expect("type missmatched.");
+    let v = args.pop().expect("type missmatched.");
+    Value::map_insert(&mut s, k, v)?;
+    Ok(Value::Map(s))
+}
+
+fn remove(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Map);
+    let o = args.pop().expect("type missmatched.");
+    if let Some(i) = Value::map_position(&s, &o) {
+        s.remove(i);
+    }
+    Ok(Value::Map(s))
+}
+
+fn contains_key(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Map);
+    let o = args.pop().expect("type missmatched.");
+    if Value::map_position(&s, &o).is_some() {
+        Ok(Value::Top)
+    } else {
+        Ok(Value::Nil)
+    }
+}
+
+fn keys(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Map);
+    Ok(Value::Array(s.into_iter().map(|(k, _)| k).collect()))
+}
+
+fn values(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Map);
+    Ok(Value::Array(s.into_iter().map(|(_, v)| v).collect()))
+}
+
+fn entries(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Map);
+    let v = s
+        .into_iter()
+        .map(|(k, v)| Value::Array(vec![k, v]))
+        .collect();
+    Ok(Value::Array(v))
+}
+
+fn merge(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let mut s = extract_variant!(s, Map);
+    let o = pop_extract_variant!(args, Map);
+    for (k, v) in o {
+        Value::map_insert(&mut s, k, v)?;
+    }
+    Ok(Value::Map(s))
+}
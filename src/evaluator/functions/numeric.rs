@@ -1,4 +1,4 @@
-use super::*;
+use super::{error::Exit, *};
 
 macro_rules! for_all_integer_types {
     ($macro: ident $(, $($arg: tt)*)?) => {
@@ -24,14 +24,82 @@ macro_rules! for_all_numeric_types {
 }
 
 macro_rules! insert_numeric_function {
-    ($fm: expr, $fn: ident, $op: tt, $ty: ident, $_: ident) => {
+    ($fm: expr, $fn: ident, $op: tt, $doc: tt, $ty: ident, $_: ident) => {
         let ty = TypeId::from(stringify!($ty));
         $fm.insert_builtins(
             &ty,
             vec![builtin_fn!(
                 stringify!($op),
                 vec![ty.clone()],
-                paste::item! {[<$fn $ty>]}
+                paste::item! {[<$fn $ty>]},
+                $doc
+            )],
+        );
+    };
+}
+
+macro_rules! insert_range {
+    ($fm: expr, $ty: ident, $_: ident) => {
+        let ty = TypeId::from(stringify!($ty));
+        $fm.insert_builtins(
+            &ty,
+            vec![
+                builtin_fn!(
+                    "..",
+                    vec![ty.clone()],
+                    paste::item! {[<range $ty>]},
+                    [
+                        "Returns an array of the integers from the subject to before the object.",
+                        "The object must be of the same type as the subject.",
+                        "The array must not have more than 16777216 elements.",
+                        "",
+                        "```fuzzy",
+                        "1 .. 5 -- [1 2 3 4]",
+                        "5 .. 1 -- []",
+                        "```",
+                    ]
+                ),
+                builtin_fn!(
+                    "../",
+                    vec![ty.clone(), ty.clone()],
+                    paste::item! {[<range_step $ty>]},
+                    [
+                        "Returns an array of the integers from the subject to before the first object,",
+                        "counting by the second object. A negative step counts down.",
+                        "The objects must be of the same type as the subject, and the step must not be 0.",
+                        "The array must not have more than 16777216 elements.",
+                        "",
+                        "```fuzzy",
+                        "0 ../ 10 3  -- [0 3 6 9]",
+                        "5 ../ 0 -2  -- [5 3 1]",
+                        "0 ../ 10 0  -- error",
+                        "```",
+                    ]
+                ),
+            ],
+        );
+    };
+}
+
+macro_rules! insert_exit {
+    ($fm: expr, $ty: ident, $_: ident) => {
+        let ty = TypeId::from(stringify!($ty));
+        $fm.insert_builtins(
+            &ty,
+            vec![builtin_fn!(
+                "!<",
+                vec![],
+                paste::item! {[<exit $ty>]},
+                [
+                    "Terminates the process with the subject as the exit code.",
+                    "Only defined for integer types, and the subject must be from `0` to `255`.",
+                    "All blocks being evaluated are exited before terminating.",
+                    "",
+                    "```fuzzy",
+                    "0 !<           -- exits successfully",
+                    "T >> { 3 !< }  -- exits with code 3",
+                    "```",
+                ]
             )],
         );
     };
@@ -45,19 +113,117 @@ macro_rules! insert_cast {
             vec![builtin_fn!(
                 ":",
                 vec![TypeId::Symbol],
-                paste::item! {[<cast $ty>]}
+                paste::item! {[<cast $ty>]},
+                [
+                    "Performs type casting.",
+                    "",
+                    "```fuzzy",
+                    "42 : 'f64      -- 42.0",
+                    "3.14f32 : 'i32 -- 3",
+                    "```",
+                ]
             )],
         );
     };
 }
 
+macro_rules! insert_try_cast {
+    ($fm: expr, $ty: ident, $_: ident) => {
+        option::insert_try_cast(
+            $fm,
+            &TypeId::from(stringify!($ty)),
+            &[
+                ("42 :? 'f64", "ok[42]"),
+                ("42 :? 'string", "err[i32 cannot cast to string.]"),
+            ],
+        );
+    };
+}
+
 pub fn insert(maps: &mut FunctionMapStack) {
-    for_all_numeric_types!(insert_numeric_function, maps, add, +);
-    for_all_numeric_types!(insert_numeric_function, maps, sub, -);
-    for_all_numeric_types!(insert_numeric_function, maps, mul, *);
-    for_all_numeric_types!(insert_numeric_function, maps, div, /);
-    for_all_integer_types!(insert_numeric_function, maps, mdd, %);
     for_all_numeric_types!(insert_cast, maps);
+    for_all_numeric_types!(insert_try_cast, maps);
+    for_all_numeric_types!(
+        insert_numeric_function,
+        maps,
+        add,
+        +,
+        [
+        "Returns the sum of the subject and the object.",
+        "The object must be of the same type as the subject.",
+        "",
+        "```fuzzy",
+        "1 + 2         -- 3",
+        "3.f32 + .5f32 -- 3.5",
+        "1 + 2.0f32    -- error",
+        "```",
+        ]
+    );
+    for_all_numeric_types!(
+        insert_numeric_function,
+        maps,
+        sub,
+        -,
+        [
+        "Returns the difference of the subject and the object.",
+        "The object must be of the same type as the subject.",
+        "",
+        "```fuzzy",
+        "1 - 2         -- -1",
+        "3.f32 - .5f32 -- 2.5",
+        "1 - 2.0f32    -- error",
+        "```",
+        ]
+    );
+    for_all_numeric_types!(
+        insert_numeric_function,
+        maps,
+        mul,
+        *,
+        [
+        "Returns the product of the subject and the object.",
+        "The object must be of the same type as the subject.",
+        "",
+        "```fuzzy",
+        "1 * 2         -- 2",
+        "3.f32 * .5f32 -- 1.5",
+        "1 * 2.0f32    -- error",
+        "```",
+        ]
+    );
+    for_all_numeric_types!(
+        insert_numeric_function,
+        maps,
+        div,
+        /,
+        [
+        "Returns the quotient of the subject and the object.",
+        "The object must be of the same type as the subject.",
+        "",
+        "```fuzzy",
+        "1 / 2         -- 0",
+        "3.f32 / .5f32 -- 6",
+        "1 / 2.0f32    -- error",
+        "```",
+        ]
+    );
+    for_all_integer_types!(
+        insert_numeric_function,
+        maps,
+        mdd,
+        %,
+        [
+        "Returns the remainder of the subject and the object.",
+        "Only defined for integer types, and the object must be of the same type as the subject.",
+        "",
+        "```fuzzy",
+        "10 % 3       -- 1",
+        "10f32 % 3f32 -- error",
+        "```",
+        ]
+    );
+    for_all_integer_types!(insert_range, maps);
+    for_all_integer_types!(insert_exit, maps);
 }
 
 macro_rules! define_numeric_function {
@@ -77,6 +243,75 @@ for_all_numeric_types!(define_numeric_function, mul, *);
 for_all_numeric_types!(define_numeric_function, div, /);
 for_all_integer_types!(define_numeric_function, mdd, %);
 
+/// The maximum number of elements of an array made by `..` or `../`.
+const MAX_RANGE_LENGTH: u128 = 1 << 24;
+
+/// A function to check the number of elements of a range before making its array.
+///
+/// * `distance` - The distance between the ends, which must be 0 if the range is empty.
+/// * `step` - The absolute value of the step.
+fn check_range_length(distance: u128, step: u128) -> RResult<()> {
+    if distance.div_ceil(step) > MAX_RANGE_LENGTH {
+        return Err(
+            format!("error: a range cannot have more than {MAX_RANGE_LENGTH} elements.").into(),
+        );
+    }
+    Ok(())
+}
+
+macro_rules! define_range {
+    ($ty: ident, $variant: ident) => {
+        paste::item! {
+            fn [<range $ty>](_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+                let s = extract_variant!(s, $variant);
+                let o = pop_extract_variant!(args, $variant);
+                let d = if s < o { u128::from(o.abs_diff(s)) } else { 0 };
+                check_range_length(d, 1)?;
+                Ok(Value::Array((s..o).map(Value::$variant).collect()))
+            }
+
+            #[allow(unused_comparisons)]
+            fn [<range_step $ty>](_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+                let s = extract_variant!(s, $variant);
+                let o = pop_extract_variant!(args, $variant);
+                let step = pop_extract_variant!(args, $variant);
+                if step == 0 {
+                    return Err("error: the step of a range must not be 0.".into());
+                }
+                let descending = step < 0;
+                let d = if descending && s > o || !descending && s < o {
+                    u128::from(o.abs_diff(s))
+                } else {
+                    0
+                };
+                check_range_length(d, u128::from(step.abs_diff(0)))?;
+                let mut v = Vec::new();
+                let mut n = Some(s);
+                while let Some(i) = n.filter(|&i| if descending { i > o } else { i < o }) {
+                    v.push(Value::$variant(i));
+                    n = i.checked_add(step);
+                }
+                Ok(Value::Array(v))
+            }
+        }
+    };
+}
+for_all_integer_types!(define_range);
+
+macro_rules! define_exit {
+    ($ty: ident, $variant: ident) => {
+        paste::item! {
+            fn [<exit $ty>](_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+                let s = extract_variant!(s, $variant);
+                let n = u8::try_from(s)
+                    .map_err(|_| format!("error: exit code {s} is out of range of 0 to 255."))?;
+                Err(Exit(n.into()).into())
+            }
+        }
+    };
+}
+for_all_integer_types!(define_exit);
+
 macro_rules! define_cast {
     ($ty: ident, $variant: ident) => {
         paste::item! {
@@ -0,0 +1,326 @@
+use super::*;
+
+pub fn insert(fm: &mut FunctionMapStack) {
+    fm.insert_builtins(
+        &TypeId::Option,
+        vec![
+            builtin_fn!(
+                "?",
+                vec![],
+                is_some,
+                [
+                    "Returns `T` if the option has a value.",
+                    "",
+                    "```fuzzy",
+                    "1 ?+ ?  -- T",
+                    "() ?- ? -- ()",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "?|",
+                vec![TypeId::Any],
+                unwrap_option_or,
+                [
+                    "Returns the value of the option, or the object if the option has no value.",
+                    "",
+                    "```fuzzy",
+                    "1 ?+ ?| 0  -- 1",
+                    "() ?- ?| 0 -- 0",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "?@",
+                vec![TypeId::Any],
+                map_option,
+                [
+                    "Calls the object with the value of the option and returns the result as an option.",
+                    "The object must be a function taking one argument.",
+                    "Returns the subject as is if the option has no value.",
+                    "",
+                    "```fuzzy",
+                    "1 ?+ ?@ { #0 + 1 } : ['i32]  -- some[2]",
+                    "() ?- ?@ { #0 + 1 } : ['i32] -- none[]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "?^",
+                vec![],
+                propagate_option,
+                [
+                    "Returns the value of the option.",
+                    "If the option has no value, the current function returns the subject immediately.",
+                    "",
+                    "```fuzzy",
+                    "{ #0 @? 5, ?^ + 1, ?+ } : ['[i32]], -> 'sixth-succ.",
+                    "sixth-succ @ [1 2 3 4 5 6] -- some[7]",
+                    "sixth-succ @ [1 2 3]       -- none[]",
+                    "```",
+                ]
+            ),
+        ],
+    );
+
+    fm.insert_builtins(
+        &TypeId::Result,
+        vec![
+            builtin_fn!(
+                "?",
+                vec![],
+                is_ok,
+                [
+                    "Returns `T` if the result is a success.",
+                    "",
+                    "```fuzzy",
+                    "1 !+ ?       -- T",
+                    "\"oops\" !- ? -- ()",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "?|",
+                vec![TypeId::Any],
+                unwrap_result_or,
+                [
+                    "Returns the value of the success, or the object if the result is an error.",
+                    "",
+                    "```fuzzy",
+                    "\"12\" :? 'i32 ?| 0  -- 12",
+                    "\"foo\" :? 'i32 ?| 0 -- 0",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "?@",
+                vec![TypeId::Any],
+                map_result,
+                [
+                    "Calls the object with the value of the success and returns the result as a success.",
+                    "The object must be a function taking one argument.",
+                    "Returns the subject as is if the result is an error.",
+                    "",
+                    "```fuzzy",
+                    "\"12\" :? 'i32 ?@ { #0 * 2 } : ['i32]  -- ok[24]",
+                    "\"foo\" :? 'i32 ?@ { #0 * 2 } : ['i32] -- err[cannot parse \"foo\" as i32.]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "?^",
+                vec![],
+                propagate_result,
+                [
+                    "Returns the value of the success.",
+                    "If the result is an error, the current function returns the subject immediately.",
+                    "",
+                    "```fuzzy",
+                    "{ #0 :? 'i32, ?^ * 2, !+ } : ['string], -> 'double.",
+                    "double @ \"12\"  -- ok[24]",
+                    "double @ \"foo\" -- err[cannot parse \"foo\" as i32.]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "?!",
+                vec![],
+                error,
+                [
+                    "Returns the value of the error as an option.",
+                    "",
+                    "```fuzzy",
+                    "1 !+ ?!      -- none[]",
+                    "\"oops\" !- ?! -- some[oops]",
+                    "```",
+                ]
+            ),
+        ],
+    );
+}
+
+/// A function to insert the verbs wrapping the subject in an option or a result.
+pub fn insert_constructors(fm: &mut FunctionMapStack, ty: &TypeId) {
+    let ex = Example::of(ty);
+    let document = |head: &str, vn: &str, printed: &dyn Fn(&str) -> String| {
+        let mut s = head.to_string();
+        if let Some(ex) = &ex {
+            s.push_str("\n\n");
+            s.push_str(&format_example(&[(
+                format!("{} {vn}", ex.less),
+                printed(&ex.printed),
+            )]));
+        }
+        s
+    };
+    fm.insert_builtins(
+        ty,
+        vec![
+            builtin_fn!(
+                "?+",
+                vec![],
+                some,
+                document(
+                    "Returns an option that has the subject as its value.",
+                    "?+",
+                    &|n| format!("some[{n}]")
+                )
+            ),
+            builtin_fn!(
+                "?-",
+                vec![],
+                none,
+                document(
+                    "Returns an option that has no value, ignoring the subject.\n\
+                     Conventionally written as `() ?-`.",
+                    "?-",
+                    &|_| "none[]".to_string()
+                )
+            ),
+            builtin_fn!(
+                "!+",
+                vec![],
+                ok,
+                document(
+                    "Returns a result that is a success of the subject.",
+                    "!+",
+                    &|n| format!("ok[{n}]")
+                )
+            ),
+            builtin_fn!(
+                "!-",
+                vec![],
+                err,
+                document(
+                    "Returns a result that is an error of the subject.",
+                    "!-",
+                    &|n| format!("err[{n}]")
+                )
+            ),
+        ],
+    );
+}
+
+/// A function to insert `:?`, the non-throwing variant of `:` on the type.
+pub fn insert_try_cast(fm: &mut FunctionMapStack, ty: &TypeId, examples: &[(&str, &str)]) {
+    let examples = examples
+        .iter()
+        .map(|(n, m)| (n.to_string(), m.to_string()))
+        .collect::<Vec<_>>();
+    let doc = format!(
+        "Performs the same conversion as `:`, but returns a result instead of throwing an error.\n\
+         The value of the error is the error message.\n\n{}",
+        format_example(&examples)
+    );
+    fm.insert_builtins(
+        ty,
+        vec![builtin_fn!(":?", vec![TypeId::Symbol], try_cast, doc)],
+    );
+}
+
+/// A function to convert the result of a throwing builtin into a `'result` value.
+///
+/// The error message without the `error: ` prefix becomes the value of the error.
+///
+/// Exits by `!<` and propagations by `?^` are not errors of the builtin, so they are passed through.
+pub fn into_result(result: RResult<Value>) -> RResult<Value> {
+    match result {
+        Ok(n) => Ok(Value::Result(Ok(Box::new(n)))),
+        Err(e) if e.is::<crate::error::Exit>() || e.is::<Propagation>() => Err(e),
+        Err(e) => {
+            let e = e.to_string();
+            let e = e.strip_prefix("error: ").unwrap_or(&e).to_string();
+            Ok(Value::Result(Err(Box::new(Value::String(e)))))
+        }
+    }
+}
+
+fn try_cast(env: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
+    let ty = s.typeid();
+    let code = env.fn_map.get_builtin(&ty, ":").map(|n| n.code.clone());
+    let Some(FunctionCode::Builtin(f)) = code else {
+        panic!(": on {ty} not defined.");
+    };
+    into_result(f(env, s, args))
+}
+
+fn some(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    Ok(Value::Option(Some(Box::new(s))))
+}
+
+fn none(_: &mut Environment, _: Value, _: Vec<Value>) -> RResult<Value> {
+    Ok(Value::Option(None))
+}
+
+fn ok(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    Ok(Value::Result(Ok(Box::new(s))))
+}
+
+fn err(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    Ok(Value::Result(Err(Box::new(s))))
+}
+
+fn is_some(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Option);
+    Ok(if s.is_some() { Value::Top } else { Value::Nil })
+}
+
+fn unwrap_option_or(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Option);
+    let o = args.pop().expect("type missmatched.");
+    Ok(s.map(|n| *n).unwrap_or(o))
+}
+
+fn map_option(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Option);
+    let o = args.pop().expect("type missmatched.");
+    let Some(n) = s else {
+        return Ok(Value::Option(None));
+    };
+    let n = lazy::call_function(env, o, vec![*n])?;
+    Ok(Value::Option(Some(Box::new(n))))
+}
+
+fn propagate_option(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Option);
+    match s {
+        Some(n) => Ok(*n),
+        None => Err(Propagation(Value::Option(None)).into()),
+    }
+}
+
+fn is_ok(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Result);
+    Ok(if s.is_ok() { Value::Top } else { Value::Nil })
+}
+
+fn unwrap_result_or(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Result);
+    let o = args.pop().expect("type missmatched.");
+    Ok(s.map(|n| *n).unwrap_or(o))
+}
+
+fn map_result(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Result);
+    let o = args.pop().expect("type missmatched.");
+    match s {
+        Ok(n) => {
+            let n = lazy::call_function(env, o, vec![*n])?;
+            Ok(Value::Result(Ok(Box::new(n))))
+        }
+        Err(n) => Ok(Value::Result(Err(n))),
+    }
+}
+
+fn propagate_result(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Result);
+    match s {
+        Ok(n) => Ok(*n),
+        Err(n) => Err(Propagation(Value::Result(Err(n))).into()),
+    }
+}
+
+fn error(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Result);
+    Ok(Value::Option(s.err()))
+}
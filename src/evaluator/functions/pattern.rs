@@ -0,0 +1,258 @@
+use super::*;
+
+pub fn insert(fm: &mut FunctionMapStack, ty: &TypeId) {
+    fm.insert_builtins(
+        ty,
+        vec![builtin_fn!(
+            "?=",
+            vec![TypeId::Array],
+            match_pattern,
+            [
+                "Evaluates the block of the first arm whose pattern matches the subject.",
+                "The object must be an array of arms.",
+                "An arm is a pattern followed by a block, optionally with a guard block between them.",
+                "An arm with a guard is selected only if the guard returns a value other than `()`.",
+                "Returns the value of the block, and throws an error if no arm is selected.",
+                "",
+                "| Pattern | Matches |",
+                "| --- | --- |",
+                "| `'_` | Any value |",
+                "| `'$name` | Any value, which is bound to `name` |",
+                "| `'$name:type` | A value of the type, which is bound to `name` |",
+                "| `'type` | A value of the type, e.g. `'i32`, `'[string]` or `'shape:circle` |",
+                "| `'=name` | The symbol `name` |",
+                "| `[pattern ...]` | An array whose elements match the patterns |",
+                "| `'..pattern` | In an array pattern, the rest of the elements as an array |",
+                "| `[':name pattern ...]` | A user-defined type value whose fields match the patterns |",
+                "| `['type ':name pattern ...]` | Same as above, and the value is of the type |",
+                "| Other values | Values equal to them |",
+                "",
+                "Variables bound by the pattern are visible in the guard and the block.",
+                "To handle all variants of a sum type with the check of exhaustiveness, use `??` instead.",
+                "",
+                "```fuzzy",
+                "[1 2 3] ?= [",
+                "  [] { 'empty }",
+                "  ['$x] { x }",
+                "  ['$x '..$rest] { rest # } { x + (rest $) }",
+                "  '_ { 'other }",
+                "]                                       -- 4",
+                "```",
+            ]
+        )],
+    );
+}
+
+fn match_pattern(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let arms = pop_extract_variant!(args, Array);
+
+    let mut arms = arms.into_iter().peekable();
+    while let Some(pattern) = arms.next() {
+        let Some(Value::Lazy(block)) = arms.next() else {
+            return Err("error: a pattern must be followed by a block.".into());
+        };
+        let (guard, block) = match arms.next_if(|n| matches!(n, Value::Lazy(_))) {
+            Some(Value::Lazy(n)) => (Some(block), n),
+            _ => (None, block),
+        };
+
+        let mut vars = Vec::new();
+        if !matches(env, &s, &pattern, &mut vars)? {
+            continue;
+        }
+        if let Some((guard, captures)) = guard {
+            let params = EnterLazyParams {
+                slf: None,
+                args: None,
+                vars: vars.clone(),
+                captures: Some(captures),
+            };
+            let n = eval_block(env, &mut guard.into(), params)?.pop();
+            if matches!(n, None | Some(Value::Nil)) {
+                continue;
+            }
+        }
+        let (block, captures) = block;
+        let params = EnterLazyParams {
+            slf: None,
+            args: None,
+            vars,
+            captures: Some(captures),
+        };
+        let result = eval_block(env, &mut block.into(), params)?
+            .pop()
+            .unwrap_or_default();
+        return Ok(result);
+    }
+
+    Err(format!("error: no pattern matched {}.", s.format_in_detail(env)).into())
+}
+
+/// A function to check if the value matches the pattern,
+/// pushing the variables bound by the pattern into `vars`.
+fn matches(
+    env: &Environment,
+    value: &Value,
+    pattern: &Value,
+    vars: &mut Vec<(String, Value)>,
+) -> RResult<bool> {
+    match pattern {
+        Value::Symbol(p) => matches_symbol(env, value, p, vars),
+        Value::Array(p) if is_field_pattern(p) => matches_fields(env, value, p, vars),
+        Value::Array(p) => {
+            let Value::Array(v) = value else {
+                return Ok(false);
+            };
+            matches_array(env, v, p, vars)
+        }
+        Value::Lazy(_) => Err("error: a block cannot be a pattern.".into()),
+        p => Ok(value.typeid() == p.typeid() && value.equal(p)),
+    }
+}
+
+fn matches_symbol(
+    env: &Environment,
+    value: &Value,
+    p: &str,
+    vars: &mut Vec<(String, Value)>,
+) -> RResult<bool> {
+    if let Some(n) = p.strip_prefix('$') {
+        let (name, ty) = match n.split_once(':') {
+            Some((n, m)) => (n, Some(m)),
+            None => (n, None),
+        };
+        if name.is_empty() {
+            return Err("error: a binding pattern must have a name.".into());
+        }
+        if ty.is_some_and(|n| !is_of_type(env, value, n)) {
+            return Ok(false);
+        }
+        // OPTIMIZE: remove clone.
+        vars.push((name.to_string(), value.clone()));
+        return Ok(true);
+    }
+    if let Some(n) = p.strip_prefix('=') {
+        return Ok(matches!(value, Value::Symbol(v) if v == n));
+    }
+    if p.starts_with("..") {
+        return Err("error: a rest pattern must be placed in an array pattern.".into());
+    }
+    Ok(is_of_type(env, value, p))
+}
+
+/// A function to check if the value is of the type written in a pattern,
+/// where `typename:variantname` is also accepted for sum types.
+fn is_of_type(env: &Environment, value: &Value, ty: &str) -> bool {
+    match (ty.split_once(':'), value) {
+        (Some((t, v)), Value::UserType((n, vn, _))) => {
+            n == &TypeId::UserDefined(t.to_string()) && vn.as_deref() == Some(v)
+        }
+        (Some(_), _) => false,
+        (None, _) => value.is_of(&TypeId::from(ty), &env.ut_map),
+    }
+}
+
+fn matches_array(
+    env: &Environment,
+    value: &[Value],
+    pattern: &[Value],
+    vars: &mut Vec<(String, Value)>,
+) -> RResult<bool> {
+    let rests = pattern
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| matches!(n, Value::Symbol(n) if n.starts_with("..")))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let Some(&i) = rests.first() else {
+        if value.len() != pattern.len() {
+            return Ok(false);
+        }
+        for (v, p) in value.iter().zip(pattern.iter()) {
+            if !matches(env, v, p, vars)? {
+                return Ok(false);
+            }
+        }
+        return Ok(true);
+    };
+    if rests.len() > 1 {
+        return Err("error: an array pattern can have only one rest pattern.".into());
+    }
+
+    let (head, tail) = (&pattern[..i], &pattern[i + 1..]);
+    if value.len() < head.len() + tail.len() {
+        return Ok(false);
+    }
+    let j = value.len() - tail.len();
+    for (v, p) in value[..i].iter().zip(head.iter()) {
+        if !matches(env, v, p, vars)? {
+            return Ok(false);
+        }
+    }
+    for (v, p) in value[j..].iter().zip(tail.iter()) {
+        if !matches(env, v, p, vars)? {
+            return Ok(false);
+        }
+    }
+    let Value::Symbol(rest) = &pattern[i] else {
+        unreachable!();
+    };
+    let rest = Value::Symbol(rest["..".len()..].to_string());
+    matches(env, &Value::Array(value[i..j].to_vec()), &rest, vars)
+}
+
+fn is_field_pattern(pattern: &[Value]) -> bool {
+    let is_field = |n: Option<&Value>| matches!(n, Some(Value::Symbol(n)) if n.starts_with(':'));
+    is_field(pattern.first())
+        || matches!(pattern.first(), Some(Value::Symbol(_))) && is_field(pattern.get(1))
+}
+
+fn matches_fields(
+    env: &Environment,
+    value: &Value,
+    pattern: &[Value],
+    vars: &mut Vec<(String, Value)>,
+) -> RResult<bool> {
+    let mut pattern = pattern;
+    if let [Value::Symbol(t), rest @ ..] = pattern
+        && !t.starts_with(':')
+    {
+        if !is_of_type(env, value, t) {
+            return Ok(false);
+        }
+        pattern = rest;
+    }
+    let Value::UserType((ty, _, fields)) = value else {
+        return Ok(false);
+    };
+
+    for n in pattern.chunks(2) {
+        let [Value::Symbol(name), p] = n else {
+            return Err(
+                "error: a field pattern must consist of pairs of a field and a pattern.".into(),
+            );
+        };
+        let (private, name) = if let Some(n) = name.strip_prefix("::") {
+            (true, n)
+        } else if let Some(n) = name.strip_prefix(':') {
+            (false, n)
+        } else {
+            return Err("error: field name must start with ':' or '::'.".into());
+        };
+        let Some(field) = fields.get(name) else {
+            return Ok(false);
+        };
+        if private != field.private {
+            let e = if field.private { "private" } else { "public" };
+            let r = if private { "private" } else { "public" };
+            return Err(format!("error: {name} of {ty} defined as {e} but specified {r}.").into());
+        }
+        if private && env.get_self_type().as_ref() != Some(ty) {
+            return Err(format!("error: {name} of {ty} is private.").into());
+        }
+        if !matches(env, &field.value, p, vars)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
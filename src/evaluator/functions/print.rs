@@ -4,12 +4,30 @@ pub fn insert(fm: &mut FunctionMapStack, ty: &TypeId) {
     fm.insert_builtins(
         ty,
         vec![
-            builtin_fn!("!", vec![], print),
-            builtin_fn!("!!", vec![], println),
+            builtin_fn!("!", vec![], print, document(ty, "!", false)),
+            builtin_fn!("!!", vec![], println, document(ty, "!!", true)),
         ],
     );
 }
 
+/// A function to generate the document of `!` or `!!`.
+fn document(ty: &TypeId, vn: &str, newline: bool) -> String {
+    let with = if newline { "with" } else { "without" };
+    let mut s = format!("Outputs to standard output {with} a newline.\nReturns the subject.");
+    if let Some(ex) = Example::of(ty) {
+        let mut lines = vec![(
+            format!("{} {vn}", ex.less),
+            format!("outputs {}", ex.printed),
+        )];
+        if newline {
+            lines.push((String::new(), "with newline".to_string()));
+        }
+        s.push_str("\n\n");
+        s.push_str(&format_example(&lines));
+    }
+    s
+}
+
 fn print(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
     print!("{s}");
     Ok(s)
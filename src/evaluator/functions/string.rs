@@ -4,15 +4,194 @@ pub fn insert(fm: &mut FunctionMapStack) {
     fm.insert_builtins(
         &TypeId::String,
         vec![
-            builtin_fn!("#", vec![], length),
-            builtin_fn!("^", vec![], first),
-            builtin_fn!("$", vec![], last),
-            builtin_fn!("@", vec![TypeId::I32], at),
-            builtin_fn!("@<", vec![TypeId::I32, TypeId::String], ins),
-            builtin_fn!("@-", vec![TypeId::I32], remove),
-            builtin_fn!("$>", vec![TypeId::String], push),
-            builtin_fn!("$-", vec![], pop),
-            builtin_fn!("=@", vec![TypeId::String, TypeId::String], replace),
+            builtin_fn!(
+                "#",
+                vec![],
+                length,
+                [
+                    "Returns the length of the string.",
+                    "The return type is `'u32`.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" # -- 5",
+                    "\"\" #      -- 0",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "^",
+                vec![],
+                first,
+                [
+                    "Returns the first character.",
+                    "The return type is `'string`.",
+                    "Returns `()` if the string is empty.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" ^ -- \"h\"",
+                    "\"\" ^      -- ()",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "$",
+                vec![],
+                last,
+                [
+                    "Returns the last character.",
+                    "The return type is `'string`.",
+                    "Returns `()` if the string is empty.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" $ -- \"o\"",
+                    "\"\" $      -- ()",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "@",
+                vec![TypeId::I32],
+                at,
+                [
+                    "Returns the character at the specified index.",
+                    "The return type is `'string`.",
+                    "Returns `()` if the index is out of bounds.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" @ 3  -- \"l\"",
+                    "\"world\" @ -1 -- \"d\"",
+                    "\"!\" @ 100    -- ()",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "@?",
+                vec![TypeId::I32],
+                try_at,
+                [
+                    "Returns the character at the specified index as an option.",
+                    "Returns `none[]` if the index is out of bounds.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" @? 3 -- some[l]",
+                    "\"!\" @? 100   -- none[]",
+                    "```",
+                ]
+            ),
+            builtin_fn!(
+                "@<",
+                vec![TypeId::I32, TypeId::String],
+                ins,
+                [
+                    "Inserts a character at the specified index.",
+                    "The object string must be a single character.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" @< 1 \"x\" -- \"hxello\"",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "@-",
+                vec![TypeId::I32],
+                remove,
+                [
+                    "Removes the character at the specified index.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" @- 1 -- \"hllo\"",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "$-",
+                vec![],
+                pop,
+                [
+                    "Removes the last character.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" $- -- \"hell\"",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "$>",
+                vec![TypeId::String],
+                push,
+                [
+                    "Concatenates the object to the end of the subject.",
+                    "",
+                    "```fuzzy",
+                    "\"Hello, \" $> \"world!\" -- \"Hello, world!\"",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "++",
+                vec![TypeId::String],
+                push,
+                [
+                    "Concatenates the object to the end of the subject, which is the same as `$>`.",
+                    "",
+                    "```fuzzy",
+                    "\"Hello, \" ++ \"world!\" -- \"Hello, world!\"",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "@:",
+                slice_types(),
+                slice,
+                [
+                    "Returns the characters from the start index to before the end index.",
+                    "Negative indices count from the end, and the end index can be omitted to take the rest.",
+                    "",
+                    "```fuzzy",
+                    "\"hello\" @: 1 3  -- \"el\"",
+                    "\"hello\" @: -3   -- \"llo\"",
+                    "\"hello\" @: 0 -1 -- \"hell\"",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "=@",
+                vec![TypeId::String, TypeId::String],
+                replace,
+                [
+                    "Replaces object1 with object2 in the subject.",
+                    "",
+                    "```fuzzy",
+                    "\"Hello, world!\" =@ \"world\" \"fuzzy\" -- \"Hello, fuzzy!\"",
+                    "\"foo bar baz\" =@ \"ba\" \"BA\"         -- \"foo BAr BAz\"",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                ":",
+                vec![TypeId::Symbol],
+                parse,
+                [
+                    "Parses the subject as a value of the type indicated by the object.",
+                    "The type must be `'bool`, numeric types, `'string` or `'symbol`.",
+                    "Throws an error if the subject is not valid for the type.",
+                    "",
+                    "```fuzzy",
+                    "\"12\" : 'i32     -- 12",
+                    "\"2.5\" : 'f64    -- 2.5",
+                    "\"T\" : 'bool     -- T",
+                    "\"foo\" : 'i32    -- error",
+                    "```"
+                ]
+            ),
+        ],
+    );
+    option::insert_try_cast(
+        fm,
+        &TypeId::String,
+        &[
+            ("\"12\" :? 'i32", "ok[12]"),
+            ("\"foo\" :? 'i32", "err[cannot parse \"foo\" as i32.]"),
         ],
     );
 }
@@ -77,15 +256,41 @@ fn push(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     Ok(Value::String(format!("{}{}", s, o)))
 }
 
+fn slice(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, String);
+    let s = s.chars().collect::<Vec<_>>();
+    let start = pop_extract_variant!(args, I32);
+    let end = args.pop().map(|n| extract_variant!(n, I32));
+    let r = convert_range(start, end, s.len())?;
+    Ok(Value::String(s[r].iter().collect()))
+}
+
 fn pop(_: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
     let mut s = extract_variant!(s, String);
     s.pop();
     Ok(Value::String(s))
 }
 
+fn try_at(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, String);
+    let s = s.chars().collect::<Vec<_>>();
+    let o = pop_extract_variant!(args, I32);
+    let n = convert_index(o, s.len())
+        .ok()
+        .map(|i| Box::new(Value::String(s[i].to_string())));
+    Ok(Value::Option(n))
+}
+
 fn replace(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
     let s = extract_variant!(s, String);
     let o = pop_extract_variant!(args, String);
     let n = pop_extract_variant!(args, String);
     Ok(Value::String(s.replace(&o, &n)))
 }
+
+fn parse(_: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, String);
+    let o = pop_extract_variant!(args, Symbol);
+    let ty = TypeId::from(&o);
+    Value::parse(&s, &ty).ok_or_else(|| format!("error: cannot parse \"{s}\" as {ty}.").into())
+}
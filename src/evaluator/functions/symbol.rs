@@ -0,0 +1,118 @@
+use super::{super::variable::Variable, error::TypeError, *};
+
+pub fn insert(fm: &mut FunctionMapStack) {
+    fm.insert_builtins(
+        &TypeId::Symbol,
+        vec![
+            builtin_fn!(
+                "%",
+                vec![],
+                eval_symbol,
+                [
+                    "Evaluates the symbol.",
+                    "",
+                    "```fuzzy",
+                    "12 -> 'a. -- define a variable named a",
+                    "'a %      -- treated as a",
+                    "          -- 12",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                ":<",
+                vec![TypeId::Symbol, TypeId::Any],
+                set_field,
+                [
+                    "Sets the field of the variable named by the subject to object2.",
+                    "The variable must be mutable and of a user-defined type.",
+                    "Object1 is the field name with the visibility prefix `:` or `::`.",
+                    "A private field can be set only in functions of the type.",
+                    "Object2 must be of the type of the field.",
+                    "Returns `()`.",
+                    "",
+                    "```fuzzy",
+                    "[':x 'i32 ':y 'i32] |> 'point.",
+                    "[':x 1 ':y 2] : 'point, -> 'p.",
+                    "'p :< ':x 5.",
+                    "p:x -- 5",
+                    "```"
+                ]
+            ),
+            builtin_fn!(
+                "&>",
+                vec![TypeId::Symbol],
+                implement,
+                [
+                    "Declares that the type named by the subject implements the interface named by the object.",
+                    "The type must have all the verbs of the interface with the same argument types.",
+                    "Builtin types can also implement interfaces.",
+                    "Returns `()`.",
+                    "",
+                    "```fuzzy",
+                    "['# []] |& 'sized.",
+                    "'string &> 'sized.",
+                    "'[] &> 'sized.",
+                    "'i32 &> 'sized     -- error because i32 doesn't have #",
+                    "```"
+                ]
+            ),
+        ],
+    );
+}
+
+fn eval_symbol(env: &mut Environment, s: Value, _: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Symbol);
+    env.vr_map.get_unwrap(env.get_self_type(), &s)
+}
+
+fn set_field(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Symbol);
+    let name = pop_extract_variant!(args, Symbol);
+    let v = args.pop().expect("type missmatched.");
+    let Some(mut n) = env.vr_map.get(&s) else {
+        return Err(format!("error: undefined variable {s} found.").into());
+    };
+    if env.vr_map.is_mutable(&s) != Some(true) {
+        return Err(format!("error: cannot change the immutable variable {s}.").into());
+    }
+    field::set(env, &mut n, &name, v)?;
+    let doc = env.vr_map.get_doc(&s);
+    let n = Variable {
+        value: n,
+        mutable: true,
+        doc,
+    };
+    env.vr_map.insert(s, n)?;
+    Ok(Value::Nil)
+}
+
+fn implement(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let s = extract_variant!(s, Symbol);
+    let o = pop_extract_variant!(args, Symbol);
+    let Some(interface) = env.ut_map.get_interface(&o) else {
+        return Err(format!("error: the interface {o} is not defined.").into());
+    };
+    let ty = TypeId::from(&s);
+    if matches!(&ty, TypeId::UserDefined(n) if env.ut_map.get(n).is_none()) {
+        return Err(format!("error: the type {ty} is not defined.").into());
+    }
+
+    for n in interface.verbs.iter() {
+        let overloads = env.fn_map.get_visible_overloads(None, &ty, &n.name);
+        let Some(f) = overloads.first() else {
+            return Err(format!("error: {ty} doesn't implement {} of {o}.", n.name).into());
+        };
+        if overloads.iter().all(|f| f.types != n.types) {
+            return Err(TypeError(format!(
+                "error: {} on {ty} takes {} but {o} requires {}.",
+                n.name,
+                TypeId::Function(f.types.clone()),
+                TypeId::Function(n.types.clone())
+            ))
+            .into());
+        }
+    }
+    env.ut_map.insert_implementor(&o, ty);
+
+    Ok(Value::Nil)
+}
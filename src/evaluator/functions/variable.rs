@@ -4,12 +4,66 @@ pub fn insert(fm: &mut FunctionMapStack, ty: &TypeId) {
     fm.insert_builtins(
         ty,
         vec![
-            builtin_fn!("->", vec![TypeId::Symbol], define_mutable),
-            builtin_fn!("=>", vec![TypeId::Symbol], define_immutable),
+            builtin_fn!(
+                "->",
+                vec![TypeId::Symbol],
+                define_mutable,
+                document(ty, "->", "a mutable", "a")
+            ),
+            builtin_fn!(
+                "=>",
+                vec![TypeId::Symbol],
+                define_immutable,
+                document(ty, "=>", "an immutable", "b")
+            ),
         ],
     );
 }
 
+/// A function to generate the document of `->` or `=>`.
+///
+/// * `kind` - `a mutable` or `an immutable`.
+/// * `var` - The variable name used in the example.
+fn document(ty: &TypeId, vn: &str, kind: &str, var: &str) -> String {
+    if let TypeId::Function(_) = ty {
+        let mutability = kind.split_once(' ').map(|n| n.1).unwrap_or(kind);
+        let lines = [
+            (
+                format!("{{ 1 + 2 }} : [], {vn} 'f."),
+                format!("defines {kind} variable called f"),
+            ),
+            (
+                format!("{{ 1 + 2 }} : [], {vn} 't:f."),
+                format!("defines {kind} function f on t"),
+            ),
+        ];
+        return format!(
+            "Defines {kind} variable or {mutability} function.\n\
+             If the object takes the form `'typename:functionname`, it defines `functionname` on `'typename`.\n\
+             The subject of the call is `##` in the function, and the arguments are `#0`, `#1`, ... in order.\n\
+             Functions with the same name and different argument types are overloads,\n\
+             and the one matching the arguments is called.\n\
+             Overloads are rejected if some arguments could be accepted by both of them.\n\
+             Returns `()`.\n\n{}",
+            format_example(&lines)
+        );
+    }
+
+    let mut s = format!("Defines {kind} variable.\nReturns `()`.");
+    if let Some(ex) = Example::of(ty) {
+        let lines = [
+            (
+                format!("{} {vn} '{var}.", ex.less),
+                format!("defines {kind} variable called {var}"),
+            ),
+            (var.to_string(), ex.less),
+        ];
+        s.push_str("\n\n");
+        s.push_str(&format_example(&lines));
+    }
+    s
+}
+
 fn define_mutable(env: &mut Environment, s: Value, args: Vec<Value>) -> RResult<Value> {
     define(env, s, args, "->", true)
 }
@@ -34,17 +88,27 @@ fn define(
     }
 
     if let Some((private, trg_ty, trg)) = split_type_and_name(&o) {
-        let Value::Function((ty, tokens)) = s else {
+        let Value::Function((ty, tokens, captures, defaults)) = s else {
             return Err(format!("error: A : is used in the object of a {arrow}, which is treated as a function definition, but the subject is not a function type.").into());
         };
 
         // check if it can redefine?
         let trg_ty = TypeId::from(trg_ty);
-        if env
-            .fn_map
-            .get(&trg_ty, trg)
-            .map(|n| !n.mutable)
-            .unwrap_or(false)
+
+        // get types
+        let TypeId::Function(types) = ty.clone() else {
+            panic!("failed to extract function.");
+        };
+
+        // check if it can redefine?
+        //
+        // NOTE: Builtin functions cannot be overloaded.
+        if env.fn_map.get_builtin(&trg_ty, trg).is_some()
+            || env
+                .fn_map
+                .get_overloads(&trg_ty, trg)
+                .iter()
+                .any(|n| n.types == types && !n.mutable)
         {
             return Err(format!(
                 "error: cannot redefine {trg} on {trg_ty} because it's immutable."
@@ -52,26 +116,26 @@ fn define(
             .into());
         }
 
-        // get types
-        let TypeId::Function(types) = ty else {
-            panic!("failed to extract function.");
-        };
-
         // insert
         let f = Function {
             mutable,
             private,
             types,
-            code: FunctionCode::UserDefined(tokens),
+            code: FunctionCode::UserDefined((ty, tokens, captures, defaults)),
+            doc: env.doc.take(),
         };
         env.fn_map
-            .insert_user_defined(&trg_ty, trg.to_string(), f)?;
+            .insert_user_defined(&env.ut_map, &trg_ty, trg.to_string(), f)?;
 
         // finish
         return Ok(Value::Nil);
     }
 
-    let v = Variable { value: s, mutable };
+    let v = Variable {
+        value: s,
+        mutable,
+        doc: env.doc.take(),
+    };
     env.vr_map.insert(o, v)?;
 
     Ok(Value::Nil)
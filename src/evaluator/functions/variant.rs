@@ -0,0 +1,94 @@
+use super::*;
+
+pub fn insert(fm: &mut FunctionMapStack, ty: &TypeId) {
+    fm.insert_builtins(
+        ty,
+        vec![builtin_fn!(
+            "??",
+            vec![TypeId::Map],
+            match_variant,
+            [
+                "Evaluates the block associated with the variant of the subject.",
+                "The object must be a map from variant names to blocks.",
+                "The key `'_` matches any variant not listed.",
+                "It's an error if a variant is handled by no block.",
+                "",
+                "In the block, the fields of the variant are bound as immutable variables.",
+                "Private fields are bound only in functions of the type.",
+                "Returns the value of the block.",
+                "",
+                "Unlike `?=`, which tries its arms in order and fails only if no arm matches the subject,",
+                "`??` checks that all variants are handled before evaluating any block,",
+                "and binds the fields without patterns.",
+                "",
+                "```fuzzy",
+                "['circle [':radius 'f64] 'rect [':width 'f64 ':height 'f64]] |+ 'shape.",
+                "[':width 2.0 ':height 3.0] : 'shape:rect",
+                "?? #['circle { radius * radius * 3.14 } 'rect { width * height }] -- 6",
+                "```",
+            ]
+        )],
+    );
+}
+
+fn match_variant(env: &mut Environment, s: Value, mut args: Vec<Value>) -> RResult<Value> {
+    let (ty, vn, fields) = extract_variant!(s, UserType);
+    let arms = pop_extract_variant!(args, Map);
+    let TypeId::UserDefined(tn) = &ty else {
+        panic!("type missmatched.");
+    };
+    let Some(ut) = env.ut_map.get(tn) else {
+        return Err(format!("error: the type {tn} not defined.").into());
+    };
+
+    let mut wildcard = false;
+    for (k, v) in arms.iter() {
+        let Value::Symbol(k) = k else {
+            return Err(
+                format!("error: the key of an arm must be a variant name but passed {k}.").into(),
+            );
+        };
+        if k == "_" {
+            wildcard = true;
+        } else if !ut.variants.iter().any(|n| &n.name == k) {
+            return Err(format!("error: {tn} doesn't have the variant {k}.").into());
+        }
+        if !matches!(v, Value::Lazy(_)) {
+            return Err(format!("error: the arm of {k} must be a lazy block.").into());
+        }
+    }
+    if !wildcard {
+        for n in ut.variants.iter() {
+            if Value::map_position(&arms, &Value::Symbol(n.name.clone())).is_none() {
+                return Err(
+                    format!("error: the variant {} of {tn} is not handled.", n.name).into(),
+                );
+            }
+        }
+    }
+
+    let vn = vn.expect("a value of a sum type must have its variant.");
+    let i = Value::map_position(&arms, &Value::Symbol(vn))
+        .or_else(|| Value::map_position(&arms, &Value::Symbol("_".to_string())))
+        .expect("an arm must be found.");
+    let Value::Lazy((arm, captures)) = arms[i].1.clone() else {
+        panic!("type missmatched.");
+    };
+
+    let private = env.get_self_type().is_some_and(|n| n == ty);
+    let vars = fields
+        .into_iter()
+        .filter(|(_, n)| private || !n.private)
+        .map(|(k, n)| (k, n.value))
+        .collect();
+    let params = EnterLazyParams {
+        slf: None,
+        args: None,
+        vars,
+        captures: Some(captures),
+    };
+    let result = eval_block(env, &mut arm.into(), params)?
+        .pop()
+        .unwrap_or_default();
+    Ok(result)
+}
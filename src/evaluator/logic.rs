@@ -1,5 +1,6 @@
 use super::{
-    functions::{FunctionCode, TypesCheckResult},
+    error::TypeError,
+    functions::{FunctionCode, TypesCheckResult, fill_args},
     types::TypeId,
     value::Value,
     *,
@@ -29,11 +30,12 @@ pub fn eval_sentence(
         //
         // NOTE: If this sentence is a clause, this ends here.
         //       This occurs in structures like `S V So1 Vo1, So2`.
+        //       The comma is left for `collect_args` to know the end of the arguments.
         if matches!(tokens.last(), Some(Token::Comma)) {
-            tokens.pop();
             if !is_toplevel {
                 break;
             }
+            tokens.pop();
         }
 
         // consume semicolon
@@ -93,8 +95,8 @@ fn eval_clause(
         return Ok(Some(s));
     };
 
-    let args = collect_args(env, tokens, caches, ty, vn.as_str())?;
-    let result = applicate(env, s, ty, vn.as_str(), args)?;
+    let (args, code) = collect_args(env, tokens, caches, ty, vn.as_str())?;
+    let result = applicate(env, s, code, args)?;
     Ok(Some(result))
 }
 
@@ -117,7 +119,7 @@ fn take_verb_name(env: &Environment, tokens: &mut Vec<Token>, ty: &TypeId) -> Op
 }
 
 fn is_valid_verb(env: &Environment, ty: &TypeId, vn: &str) -> bool {
-    is_symbol_value(ty, vn) || env.fn_map.is_defined(env.get_self_type(), ty, vn)
+    env.fn_map.is_defined(env.get_self_type(), ty, vn)
 }
 
 fn collect_args(
@@ -126,61 +128,110 @@ fn collect_args(
     caches: &mut Vec<Value>,
     ty: &TypeId,
     vn: &str,
-) -> RResult<Vec<Value>> {
-    if is_symbol_value(ty, vn) {
-        return Ok(Vec::new());
-    }
+) -> RResult<(Vec<Value>, FunctionCode)> {
+    let sty = env.get_self_type();
     let mut args = Vec::new();
-    loop {
-        match env.fn_map.check_types(ty, vn, &args) {
+    // NOTE: Whether the last argument is followed by a comma,
+    //       which ends the arguments unless more are required.
+    let mut comma = false;
+    let code = loop {
+        let at_end = comma || is_clause_end(tokens, caches);
+        match env
+            .fn_map
+            .check_types(&env.ut_map, sty.as_ref(), ty, vn, &args, at_end)
+        {
             TypesCheckResult::Undecided => (),
-            TypesCheckResult::Err(n) => return Err(n.into()),
-            TypesCheckResult::Ok => break,
+            TypesCheckResult::Err(n) => return Err(TypeError(n).into()),
+            TypesCheckResult::Ok(n) => break n,
         }
         if let Some(n) = caches.pop() {
             args.push(n);
             continue;
         }
-        let Some(n) = eval_sentence(env, tokens, caches, false)? else {
-            return Err(format!("error: too few arguments passed to {} on {}.", vn, ty).into());
+        if let Some(n) = env
+            .fn_map
+            .get_arg_type(&env.ut_map, sty.as_ref(), ty, vn, &args)
+        {
+            infer_numeric_literal(tokens, &n)?;
+        }
+        let n = eval_sentence(env, tokens, caches, false)?;
+        comma = matches!(tokens.last(), Some(Token::Comma));
+        if comma {
+            tokens.pop();
+        }
+        let Some(n) = n else {
+            return Err(TypeError(format!(
+                "error: too few arguments passed to {} on {}.",
+                vn, ty
+            ))
+            .into());
         };
         args.push(n);
-    }
+    };
     args.reverse();
-    Ok(args)
+    Ok((args, code))
+}
+
+/// A function to type the next numeric literal without suffix as the expected type.
+///
+/// e.g. In `1i64 + 1`, `1` is typed as `i64` because `+` on `i64` expects `i64`.
+fn infer_numeric_literal(tokens: &mut [Token], ty: &TypeId) -> RResult<()> {
+    let Some(token) = tokens.last_mut() else {
+        return Ok(());
+    };
+    let n = match (&*token, ty) {
+        (Token::Integer(n, _), TypeId::I8) => i8::try_from(*n).ok().map(Token::I8),
+        (Token::Integer(n, _), TypeId::U8) => u8::try_from(*n).ok().map(Token::U8),
+        (Token::Integer(n, _), TypeId::I16) => i16::try_from(*n).ok().map(Token::I16),
+        (Token::Integer(n, _), TypeId::U16) => u16::try_from(*n).ok().map(Token::U16),
+        (Token::Integer(n, _), TypeId::I32) => i32::try_from(*n).ok().map(Token::I32),
+        (Token::Integer(n, _), TypeId::U32) => u32::try_from(*n).ok().map(Token::U32),
+        (Token::Integer(n, _), TypeId::I64) => i64::try_from(*n).ok().map(Token::I64),
+        (Token::Integer(n, _), TypeId::U64) => u64::try_from(*n).ok().map(Token::U64),
+        (Token::Integer(n, _), TypeId::I128) => Some(Token::I128(*n)),
+        (Token::Integer(n, _), TypeId::U128) => u128::try_from(*n).ok().map(Token::U128),
+        (Token::LargeInteger(n, _), TypeId::U128) => Some(Token::U128(*n)),
+        (Token::LargeInteger(..), TypeId::I8 | TypeId::U8 | TypeId::I16 | TypeId::U16)
+        | (Token::LargeInteger(..), TypeId::I32 | TypeId::U32 | TypeId::I64 | TypeId::U64)
+        | (Token::LargeInteger(..), TypeId::I128) => None,
+        (Token::Float(n), TypeId::F32) => Some(Token::F32(*n as f32)),
+        (Token::Float(n), TypeId::F64) => Some(Token::F64(*n)),
+        _ => return Ok(()),
+    };
+    let Some(n) = n else {
+        return Err(token.out_of_range(ty).into());
+    };
+    *token = n;
+    Ok(())
 }
 
 fn applicate(
     env: &mut Environment,
     s: Value,
-    ty: &TypeId,
-    vn: &str,
-    args: Vec<Value>,
+    code: FunctionCode,
+    mut args: Vec<Value>,
 ) -> RResult<Value> {
-    if is_symbol_value(ty, vn) {
-        let Value::Symbol(n) = s else {
-            panic!("failed to extract symbol.");
-        };
-        return env.vr_map.get_unwrap(env.get_self_type(), &n);
-    }
-    match env.fn_map.get_code(ty, vn) {
+    match code {
         FunctionCode::Builtin(f) => (f)(env, s, args),
-        FunctionCode::UserDefined(mut tokens) => {
+        FunctionCode::UserDefined((ty, mut tokens, captures, defaults)) => {
+            // NOTE: `args` is reversed for builtin functions to pop the first argument.
+            args.reverse();
+            let TypeId::Function(types) = ty else {
+                panic!("failed to extract function.");
+            };
             let params = EnterLazyParams {
                 slf: Some(s),
-                args: Some(args),
+                args: Some(fill_args(&types, &defaults, args)),
+                vars: Vec::new(),
+                captures: Some(captures),
             };
-            let mut results = eval_block(env, &mut tokens, params)?;
-            let result = results.pop().unwrap_or_default();
-            Ok(result)
+            let result =
+                eval_block(env, &mut tokens, params).map(|mut n| n.pop().unwrap_or_default());
+            catch_propagation(result)
         }
     }
 }
 
-fn is_symbol_value(ty: &TypeId, vn: &str) -> bool {
-    matches!(ty, TypeId::Symbol) && vn == "%"
-}
-
 fn pop_cache_or_eval_element(
     env: &mut Environment,
     tokens: &mut Vec<Token>,
@@ -199,6 +250,7 @@ fn eval_element(env: &mut Environment, tokens: &mut Vec<Token>) -> RResult<Value
         Some(Token::Dot) => panic!("Token::Dot passed to eval_element."),
         Some(Token::Comma) => panic!("Token::Comma passed to eval_element."),
         Some(Token::Semicolon) => panic!("Token::Semicolon passed to eval_element."),
+        Some(Token::Doc(_)) => Err("error: a doc comment must be placed before a sentence.".into()),
         Some(Token::LParen) => {
             let mut n = extract_brackets_content(tokens, Token::LParen, Token::RParen)?;
             let result = eval_block(env, &mut n, EnterLazyParams::default())?
@@ -209,7 +261,7 @@ fn eval_element(env: &mut Environment, tokens: &mut Vec<Token>) -> RResult<Value
         Some(Token::RParen) => Err("error: unmatched ')' found.".into()),
         Some(Token::LBrace) => {
             let n = extract_brackets_content(tokens, Token::LBrace, Token::RBrace)?;
-            Ok(Value::Lazy(n.into()))
+            Ok(Value::Lazy((n.into(), env.vr_map.capture())))
         }
         Some(Token::RBrace) => Err("error: unmatched '}' found.".into()),
         Some(Token::LBracket) => {
@@ -217,6 +269,11 @@ fn eval_element(env: &mut Environment, tokens: &mut Vec<Token>) -> RResult<Value
             let results = eval_block(env, &mut n, EnterLazyParams::default())?;
             Ok(Value::Array(results))
         }
+        Some(Token::LHashBracket) => {
+            let mut n = extract_brackets_content(tokens, Token::LBracket, Token::RBracket)?;
+            let results = eval_block(env, &mut n, EnterLazyParams::default())?;
+            Value::map_from_pairs(results)
+        }
         Some(Token::RBracket) => Err("error: unmatched ']' found.".into()),
         Some(Token::Argument(n)) => env
             .get_argument(n)
@@ -234,7 +291,7 @@ fn extract_brackets_content(tokens: &mut Vec<Token>, l: Token, r: Token) -> RRes
             return Ok(result);
         } else if tokens[i] == r {
             depth -= 1;
-        } else if tokens[i] == l {
+        } else if tokens[i] == l || (l == Token::LBracket && tokens[i] == Token::LHashBracket) {
             depth += 1;
         }
     }
@@ -302,4 +359,35 @@ mod test {
         assert_eq!(tokens, tokens_expect);
         assert_eq!(result, result_expect);
     }
+
+    #[test]
+    fn test_field_doc_comments() {
+        let mut env = Environment::default();
+        let mut tokens = crate::lexer::lex(
+            "[
+               --- the horizontal position
+               ':x 'i32
+               '::y 'i32 '= 0
+             ] |> 'point.
+             [
+               'circle [
+                 --- the distance from the center
+                 ':r 'f64
+               ]
+               'square [':r 'f64]
+             ] |+ 'shape.",
+        )
+        .unwrap();
+        tokens.reverse();
+        env.prepare_block_scope(EnterLazyParams::default());
+        eval_block_directly(&mut env, &mut tokens).unwrap();
+        assert_eq!(
+            env.get_doc("point").unwrap(),
+            ":x i32 -- the horizontal position\n::y i32 = 0"
+        );
+        assert_eq!(
+            env.get_doc("shape").unwrap(),
+            "circle\n  :r f64 -- the distance from the center\nsquare\n  :r f64"
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use std::fmt::{Display, Result};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Result},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TypeId {
@@ -19,11 +22,28 @@ pub enum TypeId {
     String,
     Symbol,
     Array,
+    /// An array whose elements are all of the type.
+    ArrayOf(Box<TypeId>),
+    Map,
     Lazy,
+    Option,
+    Result,
     Function(Vec<TypeId>),
+    /// A trailing parameter that may be omitted, only found in function types.
+    /// The default value is held by the function.
+    Optional(Box<TypeId>),
+    /// The last parameter collecting the rest of the arguments into an array,
+    /// only found in function types.
+    Variadic(Box<TypeId>),
     UserDefined(String),
+    /// A type variable, whose name starts with `$` like `$T`.
+    /// It's bound to the type of the argument on each call.
+    Var(String),
 }
 
+/// Types bound to type variables in a call.
+pub type TypeBindings = HashMap<String, TypeId>;
+
 impl Display for TypeId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
         match self {
@@ -44,7 +64,11 @@ impl Display for TypeId {
             Self::String => write!(f, "string"),
             Self::Symbol => write!(f, "symbol"),
             Self::Array => write!(f, "[]"),
+            Self::ArrayOf(n) => write!(f, "[{n}]"),
+            Self::Map => write!(f, "#[]"),
             Self::Lazy => write!(f, "{{}}"),
+            Self::Option => write!(f, "option"),
+            Self::Result => write!(f, "result"),
             Self::Function(n) => {
                 let mut s = "@[".to_string();
                 for (i, t) in n.iter().enumerate() {
@@ -56,7 +80,10 @@ impl Display for TypeId {
                 s.push(']');
                 write!(f, "{s}")
             }
+            Self::Optional(n) => write!(f, "{n}?"),
+            Self::Variadic(n) => write!(f, "{n}*"),
             Self::UserDefined(n) => write!(f, "{n}"),
+            Self::Var(n) => write!(f, "{n}"),
         }
     }
 }
@@ -81,12 +108,109 @@ impl TypeId {
             "string" => Self::String,
             "symbol" => Self::Symbol,
             "[]" => Self::Array,
+            "#[]" => Self::Map,
             "{}" => Self::Lazy,
-            s => Self::UserDefined(s.to_string()),
+            "option" => Self::Option,
+            "result" => Self::Result,
+            s => {
+                if let Some(n) = s.strip_prefix('[').and_then(|n| n.strip_suffix(']')) {
+                    Self::ArrayOf(Box::new(Self::from(n)))
+                } else if s.len() > 1 && s.starts_with('$') {
+                    Self::Var(s.to_string())
+                } else {
+                    Self::UserDefined(s.to_string())
+                }
+            }
+        }
+    }
+
+    /// A function to check if the type matches the pattern,
+    /// binding type variables in the pattern to the corresponding parts of the type.
+    pub fn unify(&self, pattern: &TypeId, bindings: &mut TypeBindings) -> bool {
+        match (self, pattern) {
+            (_, Self::Var(n)) => match bindings.get(n) {
+                Some(t) => t == self,
+                None => {
+                    bindings.insert(n.clone(), self.clone());
+                    true
+                }
+            },
+            (Self::ArrayOf(t), Self::ArrayOf(p))
+            | (Self::Optional(t), Self::Optional(p))
+            | (Self::Variadic(t), Self::Variadic(p)) => t.unify(p, bindings),
+            (Self::Function(ts), Self::Function(ps)) => {
+                ts.len() == ps.len() && ts.iter().zip(ps.iter()).all(|(t, p)| t.unify(p, bindings))
+            }
+            _ => self == pattern,
+        }
+    }
+
+    /// A function to check if the type contains type variables.
+    pub fn is_generic(&self) -> bool {
+        match self {
+            Self::Var(_) => true,
+            Self::ArrayOf(t) | Self::Optional(t) | Self::Variadic(t) => t.is_generic(),
+            Self::Function(ts) => ts.iter().any(|n| n.is_generic()),
+            _ => false,
+        }
+    }
+
+    /// A function to replace bound type variables with their types.
+    pub fn substitute(&self, bindings: &TypeBindings) -> TypeId {
+        match self {
+            Self::Var(n) => bindings.get(n).cloned().unwrap_or_else(|| self.clone()),
+            Self::ArrayOf(t) => Self::ArrayOf(Box::new(t.substitute(bindings))),
+            Self::Optional(t) => Self::Optional(Box::new(t.substitute(bindings))),
+            Self::Variadic(t) => Self::Variadic(Box::new(t.substitute(bindings))),
+            Self::Function(ts) => {
+                Self::Function(ts.iter().map(|n| n.substitute(bindings)).collect())
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// A function to format the type with the types bound to its type variables,
+    /// like `[T] = [i32]`, which is used in error messages.
+    pub fn format_instantiated(&self, bindings: &TypeBindings) -> String {
+        let n = self.substitute(bindings);
+        if &n == self {
+            self.to_string()
+        } else {
+            format!("{self} = {n}")
         }
     }
 }
 
+/// A function to get the minimum and maximum numbers of arguments of a function type.
+///
+/// The maximum is `None` if the last parameter is variadic.
+pub fn arity(types: &[TypeId]) -> (usize, Option<usize>) {
+    let min = types
+        .iter()
+        .filter(|n| !matches!(n, TypeId::Optional(_) | TypeId::Variadic(_)))
+        .count();
+    match types.last() {
+        Some(TypeId::Variadic(_)) => (min, None),
+        _ => (min, Some(types.len())),
+    }
+}
+
+/// A function to get the type of the `i`-th argument of a function type,
+/// which is the element type if it's collected by the variadic parameter.
+pub fn param_type(types: &[TypeId], i: usize) -> Option<&TypeId> {
+    let n = match types.get(i) {
+        Some(n) => n,
+        None => match types.last() {
+            Some(n @ TypeId::Variadic(_)) => n,
+            _ => return None,
+        },
+    };
+    match n {
+        TypeId::Optional(n) | TypeId::Variadic(n) => Some(n),
+        n => Some(n),
+    }
+}
+
 pub const ALL_PREMITIVE_TYPES: &[TypeId] = &[
     TypeId::Bool,
     TypeId::I8,
@@ -104,5 +228,8 @@ pub const ALL_PREMITIVE_TYPES: &[TypeId] = &[
     TypeId::String,
     TypeId::Symbol,
     TypeId::Array,
+    TypeId::Map,
     TypeId::Lazy,
+    TypeId::Option,
+    TypeId::Result,
 ];
@@ -1,4 +1,4 @@
-use super::types::TypeId;
+use super::{types::TypeId, value::Value};
 use crate::RResult;
 use std::collections::HashMap;
 
@@ -7,34 +7,139 @@ pub struct UserTypeField {
     pub private: bool,
     pub name: String,
     pub ty: TypeId,
+    pub doc: Option<String>,
+    /// The value taken when the field is omitted on `: 'typename`.
+    pub default: Option<Value>,
+    /// A function taking the value of the field, which returns `()` if the value is invalid.
+    pub validator: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserTypeVariant {
+    pub name: String,
+    pub fields: Vec<UserTypeField>,
+    pub doc: Option<String>,
+}
+
+/// A user-defined type.
+///
+/// A sum type has its `variants` and no `fields`,
+/// while a product type has its `fields` and no `variants`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserType {
+    pub fields: Vec<UserTypeField>,
+    pub variants: Vec<UserTypeVariant>,
+    /// A lazy block evaluated with the value built by `: 'typename` as `##`,
+    /// whose result is used as the value instead.
+    pub constructor: Option<Value>,
+    pub doc: Option<String>,
+}
+
+impl UserType {
+    pub fn is_sum(&self) -> bool {
+        !self.variants.is_empty()
+    }
+}
+
+/// A verb required by an interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceVerb {
+    pub name: String,
+    pub types: Vec<TypeId>,
+    pub doc: Option<String>,
+}
+
+/// A named set of verb signatures, which is used as a type of arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    pub verbs: Vec<InterfaceVerb>,
+    /// Types declared to implement the interface by `&>`.
+    pub implementors: Vec<TypeId>,
+    pub doc: Option<String>,
 }
 
 #[derive(Default)]
 pub struct UserTypeMapStack {
-    map: Vec<HashMap<String, Vec<UserTypeField>>>,
+    map: Vec<HashMap<String, UserType>>,
+    interfaces: Vec<HashMap<String, Interface>>,
 }
 
 impl UserTypeMapStack {
     pub fn push(&mut self) {
         self.map.push(HashMap::new());
+        self.interfaces.push(HashMap::new());
     }
 
     pub fn pop(&mut self) {
         self.map.pop();
+        self.interfaces.pop();
     }
 
-    pub fn get(&self, name: &str) -> Option<&Vec<UserTypeField>> {
+    pub fn get(&self, name: &str) -> Option<&UserType> {
         self.map.iter().rev().find_map(|n| n.get(name))
     }
 
-    pub fn insert(&mut self, key: String, ut: Vec<UserTypeField>) -> RResult<()> {
-        if self.map.iter_mut().rev().any(|n| n.contains_key(&key)) {
+    pub fn insert(&mut self, key: String, ut: UserType) -> RResult<()> {
+        if self.is_defined(&key) {
             return Err("error: cannot redefine type.".into());
         }
+        check_type_name(&key)?;
         self.map
             .last_mut()
             .expect("user-type map stack is empty.")
             .insert(key, ut);
         Ok(())
     }
+
+    pub fn get_interface(&self, name: &str) -> Option<&Interface> {
+        self.interfaces.iter().rev().find_map(|n| n.get(name))
+    }
+
+    pub fn insert_interface(&mut self, key: String, interface: Interface) -> RResult<()> {
+        if self.is_defined(&key) {
+            return Err("error: cannot redefine type.".into());
+        }
+        check_type_name(&key)?;
+        self.interfaces
+            .last_mut()
+            .expect("user-type map stack is empty.")
+            .insert(key, interface);
+        Ok(())
+    }
+
+    /// A function to record that the type implements the interface,
+    /// which must be checked beforehand.
+    pub fn insert_implementor(&mut self, name: &str, ty: TypeId) {
+        let interface = self
+            .interfaces
+            .iter_mut()
+            .rev()
+            .find_map(|n| n.get_mut(name))
+            .unwrap_or_else(|| panic!("interface {name} not defined."));
+        if !interface.implementors.contains(&ty) {
+            interface.implementors.push(ty);
+        }
+    }
+
+    /// A function to check if the type is declared to implement the interface named `name`.
+    pub fn implements(&self, ty: &TypeId, name: &str) -> bool {
+        self.get_interface(name)
+            .is_some_and(|n| n.implementors.contains(ty))
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        self.get(name).is_some() || self.get_interface(name).is_some()
+    }
+}
+
+/// A function to check that the name can be used for a user type or an interface.
+fn check_type_name(name: &str) -> RResult<()> {
+    if name.starts_with('$') {
+        Err(
+            format!("error: type name {name} must not start with $, which marks a type variable.")
+                .into(),
+        )
+    } else {
+        Ok(())
+    }
 }
@@ -1,6 +1,12 @@
-use super::{Environment, types::TypeId};
+use super::{
+    Environment,
+    types::{TypeBindings, TypeId},
+    usertype::UserTypeMapStack,
+    variable::Captures,
+};
 use crate::{RResult, lexer::Token};
 use std::{
+    cmp::Ordering,
     collections::{HashMap, VecDeque},
     fmt::{Display, Result},
 };
@@ -31,9 +37,19 @@ pub enum Value {
     String(String),
     Symbol(String),
     Array(Vec<Value>),
-    Lazy(VecDeque<Token>),
-    Function((TypeId, Vec<Token>)),
-    UserType((TypeId, HashMap<String, Object>)),
+    /// Pairs of a key and a value in the order of insertion.
+    Map(Vec<(Value, Value)>),
+    /// A lazy block with the variable frames visible where it's created.
+    Lazy((VecDeque<Token>, Captures)),
+    /// A value that may be absent.
+    Option(Option<Box<Value>>),
+    /// A value of a success or an error.
+    Result(std::result::Result<Box<Value>, Box<Value>>),
+    /// A function with the variable frames visible where it's created
+    /// and the default values of its optional parameters.
+    Function((TypeId, Vec<Token>, Captures, Vec<Value>)),
+    /// A value of a user-defined type, with the variant name if the type is a sum type.
+    UserType((TypeId, Option<String>, HashMap<String, Object>)),
 }
 
 impl Display for Value {
@@ -66,10 +82,22 @@ impl Display for Value {
                 s.push(']');
                 write!(f, "{s}")
             }
+            Self::Map(n) => {
+                let v = n
+                    .iter()
+                    .map(|(k, v)| format!("{k} {v}"))
+                    .collect::<Vec<_>>();
+                write!(f, "#[{}]", v.join(" "))
+            }
             Self::Lazy(_) => write!(f, "{{}}"),
-            Self::Function(_) => write!(f, "{{}}"),
-            Self::UserType((_, n)) => {
-                let mut s = "[".to_string();
+            Self::Option(Some(n)) => write!(f, "some[{n}]"),
+            Self::Option(None) => write!(f, "none[]"),
+            Self::Result(Ok(n)) => write!(f, "ok[{n}]"),
+            Self::Result(Err(n)) => write!(f, "err[{n}]"),
+            Self::Function((n, _, _, _)) => write!(f, "{n}"),
+            Self::UserType((_, v, n)) => {
+                let mut s = v.clone().unwrap_or_default();
+                s.push('[');
                 let mut keys = n.keys().collect::<Vec<_>>();
                 keys.sort();
                 for (i, k) in keys.into_iter().enumerate() {
@@ -91,6 +119,11 @@ impl Value {
     pub fn from(env: &Environment, token: Token) -> RResult<Self> {
         match token {
             Token::Top => Ok(Self::Top),
+            Token::Integer(n, _) if i32::try_from(n).is_ok() => Ok(Self::I32(n as i32)),
+            Token::Integer(..) | Token::LargeInteger(..) => {
+                Err(token.out_of_range(&TypeId::I32).into())
+            }
+            Token::Float(n) => Ok(Self::F64(n)),
             Token::I8(n) => Ok(Self::I8(n)),
             Token::U8(n) => Ok(Self::U8(n)),
             Token::I16(n) => Ok(Self::I16(n)),
@@ -110,6 +143,32 @@ impl Value {
         }
     }
 
+    /// A function to parse a string as a value of the type.
+    ///
+    /// Returns `None` if the string is not valid for the type
+    /// or the type cannot be parsed from a string.
+    pub fn parse(s: &str, ty: &TypeId) -> Option<Self> {
+        match ty {
+            TypeId::Bool if s == "T" => Some(Self::Top),
+            TypeId::Bool if s == "()" => Some(Self::Nil),
+            TypeId::I8 => s.parse().ok().map(Self::I8),
+            TypeId::U8 => s.parse().ok().map(Self::U8),
+            TypeId::I16 => s.parse().ok().map(Self::I16),
+            TypeId::U16 => s.parse().ok().map(Self::U16),
+            TypeId::I32 => s.parse().ok().map(Self::I32),
+            TypeId::U32 => s.parse().ok().map(Self::U32),
+            TypeId::I64 => s.parse().ok().map(Self::I64),
+            TypeId::U64 => s.parse().ok().map(Self::U64),
+            TypeId::I128 => s.parse().ok().map(Self::I128),
+            TypeId::U128 => s.parse().ok().map(Self::U128),
+            TypeId::F32 => s.parse().ok().map(Self::F32),
+            TypeId::F64 => s.parse().ok().map(Self::F64),
+            TypeId::String => Some(Self::String(s.to_string())),
+            TypeId::Symbol => Some(Self::Symbol(s.to_string())),
+            _ => None,
+        }
+    }
+
     pub fn typeid(&self) -> TypeId {
         match self {
             Self::Nil => TypeId::Bool,
@@ -129,9 +188,66 @@ impl Value {
             Self::String(_) => TypeId::String,
             Self::Symbol(_) => TypeId::Symbol,
             Self::Array(_) => TypeId::Array,
+            Self::Map(_) => TypeId::Map,
             Self::Lazy(_) => TypeId::Lazy,
-            Self::Function((n, _)) => n.clone(),
-            Self::UserType((n, _)) => n.clone(),
+            Self::Option(_) => TypeId::Option,
+            Self::Result(_) => TypeId::Result,
+            Self::Function((n, _, _, _)) => n.clone(),
+            Self::UserType((n, _, _)) => n.clone(),
+        }
+    }
+
+    /// A function to get the type of the value,
+    /// where an array of elements of the same type is typed.
+    pub fn typeid_in_detail(&self) -> TypeId {
+        let Self::Array(n) = self else {
+            return self.typeid();
+        };
+        let Some(t) = n.first().map(|n| n.typeid_in_detail()) else {
+            return TypeId::Array;
+        };
+        // NOTE: `t` is never an interface, so no interface has to be looked up.
+        if n.iter().all(|n| n.is_of(&t, &UserTypeMapStack::default())) {
+            TypeId::ArrayOf(Box::new(t))
+        } else {
+            TypeId::Array
+        }
+    }
+
+    /// A function to check if the value can be passed where the type is expected.
+    ///
+    /// A type implementing an interface can be passed where the interface is expected.
+    pub fn is_of(&self, ty: &TypeId, ut_map: &UserTypeMapStack) -> bool {
+        match (self, ty) {
+            (_, TypeId::Any | TypeId::Var(_)) => true,
+            (Self::Array(n), TypeId::ArrayOf(t)) => n.iter().all(|n| n.is_of(t, ut_map)),
+            (_, TypeId::UserDefined(n)) if ut_map.implements(&self.typeid(), n) => true,
+            _ => &self.typeid() == ty,
+        }
+    }
+
+    /// A function to check if the value can be passed where the type is expected,
+    /// binding type variables in the type to the types of the value.
+    ///
+    /// A type variable is bound on its first occurrence,
+    /// and the following values must be of the bound type.
+    pub fn unify(
+        &self,
+        ty: &TypeId,
+        bindings: &mut TypeBindings,
+        ut_map: &UserTypeMapStack,
+    ) -> bool {
+        match (self, ty) {
+            (_, TypeId::Var(n)) => match bindings.get(n) {
+                Some(t) => self.is_of(t, ut_map),
+                None => {
+                    bindings.insert(n.clone(), self.typeid_in_detail());
+                    true
+                }
+            },
+            (Self::Array(n), TypeId::ArrayOf(t)) => n.iter().all(|n| n.unify(t, bindings, ut_map)),
+            (Self::Function((t, _, _, _)), TypeId::Function(_)) => t.unify(ty, bindings),
+            _ => self.is_of(ty, ut_map),
         }
     }
 
@@ -139,8 +255,15 @@ impl Value {
         match self {
             Self::Nil => self.to_string(),
             Self::Top => self.to_string(),
-            Self::Array(_) => self.to_string(),
+            Self::Array(_) => match self.typeid_in_detail() {
+                TypeId::Array => self.to_string(),
+                t => format!("{self} ({t})"),
+            },
+            Self::Map(_) => self.to_string(),
             Self::Lazy(_) => self.to_string(),
+            Self::Function(_) => self.to_string(),
+            Self::Option(_) => self.to_string(),
+            Self::Result(_) => self.to_string(),
             Self::Symbol(n) if env.vr_map.get(n).is_some() => {
                 let v = env.vr_map.get(n).unwrap();
                 let s = v.format_in_detail(env);
@@ -155,12 +278,11 @@ impl Value {
         }
     }
 
+    /// A function to compare values, where values of different types are never equal.
     pub fn equal(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Nil, Self::Nil) => true,
             (Self::Top, Self::Top) => true,
-            (Self::Nil, Self::Top) => false,
-            (Self::Top, Self::Nil) => false,
             (Self::I8(a), Self::I8(b)) => a == b,
             (Self::U8(a), Self::U8(b)) => a == b,
             (Self::I16(a), Self::I16(b)) => a == b,
@@ -181,57 +303,171 @@ impl Value {
                         .zip(b.iter())
                         .all(|(x, y)| x.typeid() == y.typeid() && x.equal(y))
             }
-            (Self::Lazy(a), Self::Lazy(b)) => a == b,
-            (Self::UserType((at, av)), Self::UserType((bt, bv))) => at == bt && av == bv,
-            _ => panic!("tried to compare {} and {}", self.typeid(), other.typeid(),),
+            (Self::Map(a), Self::Map(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(k, x)| {
+                        Self::map_position(b, k)
+                            .is_some_and(|i| x.typeid() == b[i].1.typeid() && x.equal(&b[i].1))
+                    })
+            }
+            (Self::Lazy((a, _)), Self::Lazy((b, _))) => a == b,
+            (Self::Function((at, ac, _, ad)), Self::Function((bt, bc, _, bd))) => {
+                at == bt && ac == bc && ad == bd
+            }
+            (Self::Option(a), Self::Option(b)) => match (a, b) {
+                (Some(a), Some(b)) => a.typeid() == b.typeid() && a.equal(b),
+                (a, b) => a.is_none() && b.is_none(),
+            },
+            (Self::Result(a), Self::Result(b)) => match (a, b) {
+                (Ok(a), Ok(b)) | (Err(a), Err(b)) => a.typeid() == b.typeid() && a.equal(b),
+                _ => false,
+            },
+            (Self::UserType((at, avn, av)), Self::UserType((bt, bvn, bv))) => {
+                at == bt && avn == bvn && av == bv
+            }
+            _ => false,
         }
     }
 
-    pub fn l(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Nil, Self::Nil) => false,
-            (Self::Top, Self::Top) => false,
-            (Self::Nil, Self::Top) => true,
-            (Self::Top, Self::Nil) => false,
-            (Self::I8(a), Self::I8(b)) => a < b,
-            (Self::U8(a), Self::U8(b)) => a < b,
-            (Self::I16(a), Self::I16(b)) => a < b,
-            (Self::U16(a), Self::U16(b)) => a < b,
-            (Self::I32(a), Self::I32(b)) => a < b,
-            (Self::U32(a), Self::U32(b)) => a < b,
-            (Self::I64(a), Self::I64(b)) => a < b,
-            (Self::U64(a), Self::U64(b)) => a < b,
-            (Self::I128(a), Self::I128(b)) => a < b,
-            (Self::U128(a), Self::U128(b)) => a < b,
-            (Self::F32(a), Self::F32(b)) => a < b,
-            (Self::F64(a), Self::F64(b)) => a < b,
-            (Self::String(a), Self::String(b)) => a < b,
-            (Self::Symbol(a), Self::Symbol(b)) => a < b,
-            _ => panic!("tried to compare {} and {}", self.typeid(), other.typeid(),),
+    /// A function to apply `f` to the captures of all lazy blocks and functions in the value.
+    pub fn for_each_captures(&mut self, f: &mut impl FnMut(&mut Captures)) {
+        match self {
+            Self::Array(n) => n.iter_mut().for_each(|n| n.for_each_captures(f)),
+            Self::Map(n) => n.iter_mut().for_each(|(k, v)| {
+                k.for_each_captures(f);
+                v.for_each_captures(f);
+            }),
+            Self::Lazy((_, c)) => f(c),
+            Self::Option(Some(n)) | Self::Result(Ok(n) | Err(n)) => n.for_each_captures(f),
+            Self::Function((_, _, c, d)) => {
+                f(c);
+                d.iter_mut().for_each(|n| n.for_each_captures(f));
+            }
+            Self::UserType((_, _, n)) => n.values_mut().for_each(|n| n.value.for_each_captures(f)),
+            _ => (),
         }
     }
 
-    pub fn g(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Self::Nil, Self::Nil) => false,
-            (Self::Top, Self::Top) => false,
-            (Self::Nil, Self::Top) => false,
-            (Self::Top, Self::Nil) => true,
-            (Self::I8(a), Self::I8(b)) => a > b,
-            (Self::U8(a), Self::U8(b)) => a > b,
-            (Self::I16(a), Self::I16(b)) => a > b,
-            (Self::U16(a), Self::U16(b)) => a > b,
-            (Self::I32(a), Self::I32(b)) => a > b,
-            (Self::U32(a), Self::U32(b)) => a > b,
-            (Self::I64(a), Self::I64(b)) => a > b,
-            (Self::U64(a), Self::U64(b)) => a > b,
-            (Self::I128(a), Self::I128(b)) => a > b,
-            (Self::U128(a), Self::U128(b)) => a > b,
-            (Self::F32(a), Self::F32(b)) => a > b,
-            (Self::F64(a), Self::F64(b)) => a > b,
-            (Self::String(a), Self::String(b)) => a > b,
-            (Self::Symbol(a), Self::Symbol(b)) => a > b,
-            _ => panic!("tried to compare {} and {}", self.typeid(), other.typeid(),),
+    /// A function to create a map from keys and values placed alternately.
+    ///
+    /// If a key appears more than once, the last value is taken.
+    pub fn map_from_pairs(values: Vec<Self>) -> RResult<Self> {
+        if !values.len().is_multiple_of(2) {
+            return Err("error: a map literal must consist of pairs of a key and a value.".into());
         }
+        let mut map = Vec::new();
+        let mut values = values.into_iter();
+        while let (Some(k), Some(v)) = (values.next(), values.next()) {
+            Self::map_insert(&mut map, k, v)?;
+        }
+        Ok(Self::Map(map))
+    }
+
+    /// A function to insert a pair into a map, replacing the value of the same key.
+    pub fn map_insert(map: &mut Vec<(Self, Self)>, k: Self, v: Self) -> RResult<()> {
+        if !matches!(
+            k,
+            Self::String(_)
+                | Self::Symbol(_)
+                | Self::I8(_)
+                | Self::U8(_)
+                | Self::I16(_)
+                | Self::U16(_)
+                | Self::I32(_)
+                | Self::U32(_)
+                | Self::I64(_)
+                | Self::U64(_)
+                | Self::I128(_)
+                | Self::U128(_)
+        ) {
+            return Err(format!(
+                "error: the key of a map must be string, symbol or integer but passed {}.",
+                k.typeid()
+            )
+            .into());
+        }
+        match Self::map_position(map, &k) {
+            Some(i) => map[i].1 = v,
+            None => map.push((k, v)),
+        }
+        Ok(())
+    }
+
+    /// A function to find the index of the pair with the key in a map.
+    pub fn map_position(map: &[(Self, Self)], k: &Self) -> Option<usize> {
+        map.iter()
+            .position(|(n, _)| n.typeid() == k.typeid() && n.equal(k))
+    }
+
+    /// A function to compare values of the same type,
+    /// where arrays are compared lexicographically.
+    ///
+    /// Returns `None` if the values are unordered like NaN.
+    pub fn partial_compare(&self, other: &Self) -> RResult<Option<Ordering>> {
+        let n = match (self, other) {
+            (Self::Nil, Self::Nil) | (Self::Top, Self::Top) => Some(Ordering::Equal),
+            (Self::Nil, Self::Top) => Some(Ordering::Less),
+            (Self::Top, Self::Nil) => Some(Ordering::Greater),
+            (Self::I8(a), Self::I8(b)) => a.partial_cmp(b),
+            (Self::U8(a), Self::U8(b)) => a.partial_cmp(b),
+            (Self::I16(a), Self::I16(b)) => a.partial_cmp(b),
+            (Self::U16(a), Self::U16(b)) => a.partial_cmp(b),
+            (Self::I32(a), Self::I32(b)) => a.partial_cmp(b),
+            (Self::U32(a), Self::U32(b)) => a.partial_cmp(b),
+            (Self::I64(a), Self::I64(b)) => a.partial_cmp(b),
+            (Self::U64(a), Self::U64(b)) => a.partial_cmp(b),
+            (Self::I128(a), Self::I128(b)) => a.partial_cmp(b),
+            (Self::U128(a), Self::U128(b)) => a.partial_cmp(b),
+            (Self::F32(a), Self::F32(b)) => a.partial_cmp(b),
+            (Self::F64(a), Self::F64(b)) => a.partial_cmp(b),
+            (Self::String(a), Self::String(b)) => a.partial_cmp(b),
+            (Self::Symbol(a), Self::Symbol(b)) => a.partial_cmp(b),
+            (Self::Array(a), Self::Array(b)) => {
+                for (x, y) in a.iter().zip(b.iter()) {
+                    match x.partial_compare(y)? {
+                        Some(Ordering::Equal) => (),
+                        n => return Ok(n),
+                    }
+                }
+                a.len().partial_cmp(&b.len())
+            }
+            _ => {
+                return Err(format!(
+                    "error: cannot compare {} and {}.",
+                    self.typeid(),
+                    other.typeid()
+                )
+                .into());
+            }
+        };
+        Ok(n)
+    }
+
+    /// A function to compare values like `partial_compare`,
+    /// but unordered values are an error.
+    pub fn compare(&self, other: &Self) -> RResult<Ordering> {
+        self.partial_compare(other)?
+            .ok_or_else(|| format!("error: {self} and {other} are unordered.").into())
+    }
+
+    pub fn l(&self, other: &Self) -> RResult<bool> {
+        Ok(self.partial_compare(other)? == Some(Ordering::Less))
+    }
+
+    pub fn g(&self, other: &Self) -> RResult<bool> {
+        Ok(self.partial_compare(other)? == Some(Ordering::Greater))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_equal_different_types() {
+        assert!(!Value::I32(1).equal(&Value::String("1".to_string())));
+        assert!(!Value::Nil.equal(&Value::Top));
+        assert!(!Value::Array(vec![]).equal(&Value::Map(vec![])));
+        assert!(Value::I32(1).equal(&Value::I32(1)));
     }
 }
@@ -1,40 +1,171 @@
 use super::{types::TypeId, value::Value};
 use crate::RResult;
-use std::collections::HashMap;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
 
 pub struct Variable {
     pub value: Value,
     pub mutable: bool,
+    pub doc: Option<String>,
+}
+
+/// Variables defined in a scope.
+///
+/// NOTE: A frame is shared by the scope and the lazy blocks and functions created in it,
+///       so that changes of a mutable variable are visible from both of them.
+type Frame = Rc<RefCell<HashMap<String, Variable>>>;
+
+/// A frame captured by a lazy block or a function.
+///
+/// NOTE: While a lazy block or a function is stored in a frame, the frame and its outer frames
+///       are held weakly, because holding them strongly makes a reference cycle never freed.
+///       They are held strongly again when the value is taken out of the frame.
+#[derive(Clone)]
+enum CapturedFrame {
+    Strong(Frame),
+    Weak(Weak<RefCell<HashMap<String, Variable>>>),
+}
+
+/// Variable frames captured by a lazy block or a function where it's created.
+///
+/// Captured frames don't take part in the equality of values,
+/// so lazy blocks and functions with the same code are equal wherever they are created.
+#[derive(Clone, Default)]
+pub struct Captures(Vec<CapturedFrame>);
+
+impl Captures {
+    /// A function to create a single frame of immutable variables.
+    pub fn with(vars: Vec<(String, Value)>) -> Self {
+        let frame = vars
+            .into_iter()
+            .map(|(k, value)| {
+                let n = Variable {
+                    value,
+                    mutable: false,
+                    doc: None,
+                };
+                (k, n)
+            })
+            .collect();
+        Captures(vec![CapturedFrame::Strong(Rc::new(RefCell::new(frame)))])
+    }
+
+    /// A function to hold the frames in `frames` weakly.
+    pub fn downgrade(&mut self, frames: &[Frame]) {
+        for n in self.0.iter_mut() {
+            if let CapturedFrame::Strong(f) = n
+                && frames.iter().any(|m| Rc::ptr_eq(f, m))
+            {
+                *n = CapturedFrame::Weak(Rc::downgrade(f));
+            }
+        }
+    }
+
+    /// A function to hold the frames strongly again.
+    pub fn upgrade(&mut self) {
+        for n in self.0.iter_mut() {
+            if let CapturedFrame::Weak(f) = n
+                && let Some(f) = f.upgrade()
+            {
+                *n = CapturedFrame::Strong(f);
+            }
+        }
+    }
+
+    /// A function to get the frames, where frames already freed are replaced with empty ones.
+    fn into_frames(self) -> Vec<Frame> {
+        self.0
+            .into_iter()
+            .map(|n| match n {
+                CapturedFrame::Strong(f) => f,
+                CapturedFrame::Weak(f) => f.upgrade().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+impl PartialEq for Captures {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Captures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Captures({} frames)", self.0.len())
+    }
 }
 
 #[derive(Default)]
 pub struct VariableMapStack {
-    map: Vec<HashMap<String, Variable>>,
+    map: Vec<Frame>,
 }
 
 impl VariableMapStack {
     pub fn push(&mut self) {
-        self.map.push(HashMap::new());
+        self.map.push(Rc::new(RefCell::new(HashMap::new())));
     }
 
     pub fn pop(&mut self) {
         self.map.pop();
     }
 
+    /// A function to capture the frames visible in the current scope.
+    pub fn capture(&self) -> Captures {
+        Captures(
+            self.map
+                .iter()
+                .cloned()
+                .map(CapturedFrame::Strong)
+                .collect(),
+        )
+    }
+
+    /// A function to replace the visible frames with the captured ones,
+    /// returning the frames replaced, which must be restored by `restore`.
+    pub fn enter(&mut self, captures: Captures) -> Captures {
+        let n = std::mem::replace(&mut self.map, captures.into_frames());
+        Captures(n.into_iter().map(CapturedFrame::Strong).collect())
+    }
+
+    pub fn restore(&mut self, captures: Captures) {
+        self.map = captures.into_frames();
+    }
+
     pub fn is_mutable(&self, name: &str) -> Option<bool> {
         self.map
             .iter()
             .rev()
-            .find_map(|n| n.get(name))
-            .map(|n| n.mutable)
+            .find_map(|n| n.borrow().get(name).map(|n| n.mutable))
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        // OPTIMIZE: remove clone.
+        let mut n = self
+            .map
+            .iter()
+            .rev()
+            .find_map(|n| n.borrow().get(name).map(|n| n.value.clone()))?;
+        n.for_each_captures(&mut Captures::upgrade);
+        Some(n)
+    }
+
+    pub fn get_type(&self, name: &str) -> Option<TypeId> {
+        self.map
+            .iter()
+            .rev()
+            .find_map(|n| n.borrow().get(name).map(|n| n.value.typeid()))
     }
 
-    pub fn get(&self, name: &str) -> Option<&Value> {
+    pub fn get_doc(&self, name: &str) -> Option<String> {
         self.map
             .iter()
             .rev()
-            .find_map(|n| n.get(name))
-            .map(|n| &n.value)
+            .find_map(|n| n.borrow().get(name).map(|n| n.doc.clone()))
+            .flatten()
     }
 
     pub fn get_unwrap(&self, sty: Option<TypeId>, name: &str) -> RResult<Value> {
@@ -42,10 +173,10 @@ impl VariableMapStack {
             let Some(n) = self.get(pn) else {
                 return Err(format!("error: undefined variable {pn} found.").into());
             };
-            let Value::UserType((ty, n)) = n else {
+            let Value::UserType((ty, _, mut n)) = n else {
                 return Err(format!("error: {pn} is builtin-type but it has no field.").into());
             };
-            let Some(n) = n.get(cn) else {
+            let Some(n) = n.remove(cn) else {
                 return Err(format!("error: {pn} doesn't have the member {cn}.").into());
             };
             if private != n.private {
@@ -55,21 +186,25 @@ impl VariableMapStack {
                     format!("error: {cn} of {pn} defined as {e} but specified {r}.").into(),
                 );
             }
-            if private && !sty.map(|n| &n == ty).unwrap_or(false) {
+            if private && sty.as_ref() != Some(&ty) {
                 return Err(format!("error: {cn} of {pn} is private.").into());
             }
-            return Ok(n.value.clone());
+            return Ok(n.value);
         }
         if let Some(n) = self.get(name) {
-            // OPTIMIZE: remove clone.
-            Ok(n.clone())
+            Ok(n)
         } else {
             Err(format!("error: undefined variable {name} found.").into())
         }
     }
 
-    pub fn insert(&mut self, key: String, value: Variable) -> RResult<()> {
-        if let Some(n) = self.map.iter_mut().rev().find_map(|n| n.get_mut(&key)) {
+    pub fn insert(&mut self, key: String, mut value: Variable) -> RResult<()> {
+        if let Some(i) = self.map.iter().rposition(|n| n.borrow().contains_key(&key)) {
+            value
+                .value
+                .for_each_captures(&mut |n| n.downgrade(&self.map[..=i]));
+            let mut n = self.map[i].borrow_mut();
+            let n = n.get_mut(&key).unwrap();
             if n.mutable {
                 *n = value;
                 Ok(())
@@ -77,22 +212,45 @@ impl VariableMapStack {
                 Err(format!("error: cannot redefine variable {key}.").into())
             }
         } else {
+            value
+                .value
+                .for_each_captures(&mut |n| n.downgrade(&self.map));
             self.map
-                .last_mut()
+                .last()
                 .expect("variable map stack is empty.")
+                .borrow_mut()
                 .insert(key, value);
             Ok(())
         }
     }
 
-    pub fn insert_self(&mut self, value: Value) {
+    /// A function to define an immutable variable in the innermost scope,
+    /// shadowing the variables of the same name in outer scopes.
+    pub fn insert_local(&mut self, key: String, mut value: Value) {
+        value.for_each_captures(&mut |n| n.downgrade(&self.map));
+        let n = Variable {
+            mutable: false,
+            value,
+            doc: None,
+        };
+        self.map
+            .last()
+            .expect("variable map stack is empty.")
+            .borrow_mut()
+            .insert(key, n);
+    }
+
+    pub fn insert_self(&mut self, mut value: Value) {
+        value.for_each_captures(&mut |n| n.downgrade(&self.map));
         let n = Variable {
             mutable: true,
             value,
+            doc: None,
         };
         self.map
-            .last_mut()
+            .last()
             .expect("variable map stack is empty.")
+            .borrow_mut()
             .insert("##".to_string(), n);
     }
 }
@@ -106,3 +264,36 @@ fn split_member_access(name: &str) -> Option<(&str, &str, bool)> {
         None
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn store_closure() -> (VariableMapStack, Weak<RefCell<HashMap<String, Variable>>>) {
+        let mut vr_map = VariableMapStack::default();
+        vr_map.push();
+        vr_map.push();
+        let frame = Rc::downgrade(vr_map.map.last().unwrap());
+        let n = Value::Lazy((VecDeque::new(), vr_map.capture()));
+        vr_map.insert_local("f".to_string(), n);
+        (vr_map, frame)
+    }
+
+    #[test]
+    fn test_closure_stored_in_captured_frame_is_freed() {
+        let (mut vr_map, frame) = store_closure();
+        vr_map.pop();
+        assert!(frame.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_closure_taken_out_keeps_captured_frame() {
+        let (mut vr_map, frame) = store_closure();
+        let n = vr_map.get("f").unwrap();
+        vr_map.pop();
+        assert!(frame.upgrade().is_some());
+        drop(n);
+        assert!(frame.upgrade().is_none());
+    }
+}
@@ -1,6 +1,25 @@
-use crate::*;
+use crate::{
+    error::{LexError, TypeError},
+    *,
+};
 use regex::Regex;
-use std::fmt::{Display, Result};
+use std::{
+    fmt::{Display, Result},
+    num::IntErrorKind,
+};
+
+/// The 1-based line and column of a token in the source code.
+///
+/// Positions are ignored on comparison
+/// so that the same code written at different places is equal.
+#[derive(serde::Serialize, Debug, Clone, Copy)]
+pub struct Position(pub usize, pub usize);
+
+impl PartialEq for Position {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
 
 #[derive(serde::Serialize, Debug, Clone, PartialEq)]
 pub enum Token {
@@ -14,8 +33,20 @@ pub enum Token {
     RBrace,
     LBracket,
     RBracket,
+    /// `#[`, which opens a map literal closed by `]`.
+    LHashBracket,
+    /// A doc comment attached to the following definition.
+    Doc(String),
     // atoms
     Top,
+    /// An integer literal without suffix, which is `i32` unless its type is inferred.
+    /// It's kept wide until then so that it can be inferred as a wider type,
+    /// with its position to report if it's out of range of the type.
+    Integer(i128, Option<Position>),
+    /// An integer literal without suffix that only fits in `u128`.
+    LargeInteger(u128, Option<Position>),
+    /// A float literal without suffix, which is `f64` unless its type is inferred.
+    Float(f64),
     I8(i8),
     U8(u8),
     I16(i16),
@@ -46,7 +77,12 @@ impl Display for Token {
             Self::RBrace => write!(f, "}}"),
             Self::LBracket => write!(f, "["),
             Self::RBracket => write!(f, "]"),
+            Self::LHashBracket => write!(f, "#["),
+            Self::Doc(n) => write!(f, "--- {n}"),
             Self::Top => write!(f, "T"),
+            Self::Integer(n, _) => write!(f, "{n}"),
+            Self::LargeInteger(n, _) => write!(f, "{n}"),
+            Self::Float(n) => write!(f, "{n}"),
             Self::I8(n) => write!(f, "{n}"),
             Self::U8(n) => write!(f, "{n}"),
             Self::I16(n) => write!(f, "{n}"),
@@ -68,8 +104,33 @@ impl Display for Token {
 }
 
 impl Token {
+    /// A function to create the error of an integer literal without suffix
+    /// out of range of the type, which is found when its type is decided.
+    pub fn out_of_range(&self, ty: &impl Display) -> TypeError {
+        match self {
+            Self::Integer(_, Some(Position(l, c)))
+            | Self::LargeInteger(_, Some(Position(l, c))) => TypeError(format!(
+                "error: {l}:{c}: the literal {self} is out of range of {ty}."
+            )),
+            _ => TypeError(format!(
+                "error: the literal {self} is out of range of {ty}."
+            )),
+        }
+    }
+
+    /// A function to convert a string into a token.
+    ///
+    /// Falls back to `Token::Label` if the string is not a valid token.
     pub fn from(s: &str) -> Self {
-        if s == "." {
+        Self::parse(s).unwrap_or_else(|_| Self::Label(s.to_string()))
+    }
+
+    /// A function to convert a string into a token.
+    ///
+    /// Returns the error message without the position
+    /// if the string looks like a literal but is invalid.
+    fn parse(s: &str) -> std::result::Result<Self, String> {
+        let n = if s == "." {
             Self::Dot
         } else if s == "," {
             Self::Comma
@@ -90,43 +151,192 @@ impl Token {
         } else if s == "T" {
             Self::Top
         } else if let Some(n) = parse_number(s) {
+            n?
+        } else if let Some(n) = scan_string(s) {
+            let (n, l) = n?;
+            if l != s.len() {
+                return Err("a string literal must be followed by a space or sign.".to_string());
+            }
             n
-        } else if s.starts_with("\"") && s.ends_with("\"") {
-            Self::String(parse_string_literal(&s[1..s.len() - 1]))
+        } else if s.contains('"') {
+            return Err(format!("stray '\"' found in '{s}'."));
+        } else if s == "'" {
+            return Err("symbol name is empty.".to_string());
         } else if s.starts_with("'") {
             Self::Symbol(s[1..s.len()].to_string())
         } else if let Some(n) = parse_argument(s) {
             n
         } else {
             Self::Label(s.to_string())
-        }
+        };
+        Ok(n)
     }
 }
 
 pub fn lex(code: &str) -> RResult<Vec<Token>> {
     let mut tokens = Vec::new();
-    let regex = Regex::new(r#""(?:[^"\\]|\\.)*"|[(\{\[)\}\]]|\S+|\.|,|;"#)?;
-    for l in code.lines() {
-        let l = l.find("--").map(|n| &l[..n]).unwrap_or(l);
-        let i = regex
-            .find_iter(l)
-            .flat_map(|n| split_trailing_signs(n.as_str()))
-            .filter(|n| !n.is_empty())
-            .map(Token::from);
-        tokens.extend(i);
+    let mut rest = code;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let err = |e| {
+            let Position(l, c) = position(code, code.len() - rest.len());
+            LexError(format!("error: {l}:{c}: {e}"))
+        };
+
+        // block comment
+        if rest.starts_with("-{") {
+            let Some(l) = scan_block_comment(rest) else {
+                return Err(err("unterminated block comment.".to_string()).into());
+            };
+            rest = &rest[l..];
+            continue;
+        }
+
+        // doc comment
+        if rest.starts_with("---") && !rest.starts_with("----") {
+            let l = rest.find('\n').unwrap_or(rest.len());
+            let n = rest[3..l]
+                .strip_prefix(' ')
+                .unwrap_or(&rest[3..l])
+                .trim_end();
+            if let Some(Token::Doc(m)) = tokens.last_mut() {
+                m.push('\n');
+                m.push_str(n);
+            } else {
+                tokens.push(Token::Doc(n.to_string()));
+            }
+            rest = &rest[l..];
+            continue;
+        }
+
+        // comment
+        if rest.starts_with("--") {
+            rest = rest.find('\n').map(|n| &rest[n..]).unwrap_or("");
+            continue;
+        }
+
+        // string
+        if let Some(n) = scan_string(rest) {
+            let (n, l) = n.map_err(err)?;
+            tokens.push(n);
+            rest = &rest[l..];
+            continue;
+        }
+
+        // map bracket
+        if rest.starts_with("#[") {
+            tokens.push(Token::LHashBracket);
+            rest = &rest[2..];
+            continue;
+        }
+
+        // bracket
+        if rest.starts_with(['(', ')', '{', '}', '[', ']']) {
+            tokens.push(Token::from(&rest[..1]));
+            rest = &rest[1..];
+            continue;
+        }
+
+        // word
+        let l = rest.find(|c: char| c.is_whitespace()).unwrap_or(rest.len());
+        let l = rest[..l].find("--").unwrap_or(l);
+        for n in split_trailing_signs(&rest[..l]) {
+            if !n.is_empty() {
+                let mut n = Token::parse(n).map_err(err)?;
+                // NOTE: A number literal is always the first part of the word.
+                if let Token::Integer(_, p) | Token::LargeInteger(_, p) = &mut n {
+                    *p = Some(position(code, code.len() - rest.len()));
+                }
+                tokens.push(n);
+            }
+        }
+        rest = &rest[l..];
     }
     Ok(tokens)
 }
 
+/// A function to get the byte length of the block comment at the beginning of `s`.
+///
+/// Block comments are enclosed in `-{` and `}-`, and can be nested.
+/// Returns `None` if it's not terminated.
+fn scan_block_comment(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < s.len() {
+        if s[i..].starts_with("-{") {
+            depth += 1;
+            i += 2;
+        } else if s[i..].starts_with("}-") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += s[i..].chars().next()?.len_utf8();
+        }
+    }
+    None
+}
+
+/// A function to get the 1-based line and column of the byte offset.
+fn position(code: &str, offset: usize) -> Position {
+    let code = &code[..offset];
+    let l = code.matches('\n').count() + 1;
+    let c = code.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    Position(l, c)
+}
+
+/// A function to scan a string literal at the beginning of `s`.
+///
+/// Returns `None` if `s` doesn't start with a string literal,
+/// or the token and the byte length of the literal.
+///
+/// Supports the following notations:
+///
+/// * `"..."` - A string literal with escape sequences.
+/// * `r"..."`, `r#"..."#`, ... - A raw string literal without escape sequences.
+///   It ends with `"` followed by the same number of `#` as the beginning.
+///
+/// Both can span multiple lines.
+fn scan_string(s: &str) -> Option<std::result::Result<(Token, usize), String>> {
+    if let Some(n) = s.strip_prefix('"') {
+        let mut chars = n.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    let n = parse_string_literal(&n[..i]).map(Token::String);
+                    return Some(n.map(|n| (n, i + 2)));
+                }
+                '\\' => {
+                    chars.next();
+                }
+                _ => (),
+            }
+        }
+        return Some(Err("unterminated string literal.".to_string()));
+    }
+
+    let n = s.strip_prefix('r')?;
+    let hashes = n.len() - n.trim_start_matches('#').len();
+    let n = n[hashes..].strip_prefix('"')?;
+    let close = format!("\"{}", "#".repeat(hashes));
+    let Some(i) = n.find(&close) else {
+        return Some(Err("unterminated raw string literal.".to_string()));
+    };
+    let l = 1 + hashes + 1 + i + close.len();
+    Some(Ok((Token::String(n[..i].to_string()), l)))
+}
+
 fn split_trailing_signs(s: &str) -> Vec<&str> {
-    if is_sign_str(s) {
+    // NOTE: `..` is the only label consisting of signs only.
+    if is_sign_str(s) || s == ".." {
         return vec![s];
     }
-    let spos = if s.starts_with("'[]") || s.starts_with("'{}") {
-        3
-    } else {
-        0
-    };
+    let spos = bracketed_type_len(s);
     let pos = s[spos..]
         .rfind(|c: char| !is_sign_char(c))
         .map(|i| spos + i + 1)
@@ -139,6 +349,30 @@ fn split_trailing_signs(s: &str) -> Vec<&str> {
     v
 }
 
+/// A function to get the byte length of a symbol of a bracketed type name at the beginning of `s`,
+/// such as `'[]`, `'{}`, `'#[]` and `'[i32]`, so that its brackets aren't split as signs.
+fn bracketed_type_len(s: &str) -> usize {
+    let Some(n) = s.strip_prefix('\'') else {
+        return 0;
+    };
+    let n = n.strip_prefix('#').unwrap_or(n);
+    let mut depth = 0;
+    for (i, c) in n.char_indices() {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    return s.len() - n.len() + i + 1;
+                }
+            }
+            _ if depth == 0 => return 0,
+            _ => (),
+        }
+    }
+    0
+}
+
 fn is_sign_str(s: &str) -> bool {
     if s.len() == 1 {
         is_sign_char(s.chars().next().unwrap())
@@ -151,30 +385,102 @@ fn is_sign_char(c: char) -> bool {
     matches!(c, '.' | ',' | ';' | '(' | ')' | '{' | '}' | '[' | ']')
 }
 
-fn parse_number(s: &str) -> Option<Token> {
-    let caps = Regex::new(r"(.*?)(i8|u8|i16|u16|i32|u32|i64|u64|i128|u128|f32|f64)?$")
-        .ok()?
-        .captures(s)?;
-    let f = caps.get(1)?.as_str();
-    let l = caps.get(2).map(|n| n.as_str());
-    match (f, l) {
-        (n, None) => n.parse::<i32>().ok().map(Token::I32),
-        (n, Some("i8")) => n.parse::<i8>().ok().map(Token::I8),
-        (n, Some("u8")) => n.parse::<u8>().ok().map(Token::U8),
-        (n, Some("i16")) => n.parse::<i16>().ok().map(Token::I16),
-        (n, Some("u16")) => n.parse::<u16>().ok().map(Token::U16),
-        (n, Some("i32")) => n.parse::<i32>().ok().map(Token::I32),
-        (n, Some("u32")) => n.parse::<u32>().ok().map(Token::U32),
-        (n, Some("i64")) => n.parse::<i64>().ok().map(Token::I64),
-        (n, Some("u64")) => n.parse::<u64>().ok().map(Token::U64),
-        (n, Some("i128")) => n.parse::<i128>().ok().map(Token::I128),
-        (n, Some("u128")) => n.parse::<u128>().ok().map(Token::U128),
-        (n, Some("f32")) => n.parse::<f32>().ok().map(Token::F32),
-        (n, Some("f64")) => n.parse::<f64>().ok().map(Token::F64),
+macro_rules! parse_integer {
+    ($n: expr, $radix: expr, $ty: ident, $token: expr) => {
+        match $ty::from_str_radix(&$n, $radix) {
+            Ok(n) => Some(Ok($token(n))),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+                ) =>
+            {
+                Some(Err(format!(
+                    "the literal is out of range of {}.",
+                    stringify!($ty)
+                )))
+            }
+            // NOTE: Unsigned types reject a minus sign as an invalid digit.
+            Err(_) if $n.starts_with('-') => match $ty::from_str_radix(&$n[1..], $radix) {
+                Ok(0) => Some(Ok($token(0))),
+                Ok(_) => Some(Err(format!(
+                    "the literal is out of range of {}.",
+                    stringify!($ty)
+                ))),
+                Err(_) => None,
+            },
+            Err(_) => None,
+        }
+    };
+}
+
+/// A function to parse a number literal.
+///
+/// Returns `None` if the string doesn't start like a number literal,
+/// or `Some(Err)` if it does but is malformed or its value overflows.
+fn parse_number(s: &str) -> Option<std::result::Result<Token, String>> {
+    let caps =
+        Regex::new(r"^(-?)(0x|0o|0b)?(.*?)(i8|u8|i16|u16|i32|u32|i64|u64|i128|u128|f32|f64)?$")
+            .ok()?
+            .captures(s)?;
+    let sign = caps.get(1)?.as_str();
+    let radix = match caps.get(2).map(|n| n.as_str()) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => 10,
+    };
+    let mut f = caps.get(3)?.as_str().to_string();
+    let mut l = caps.get(4).map(|n| n.as_str());
+
+    // NOTE: Since `f` is a hexadecimal digit, `0x1f32` is `0x1f32` but not `0x1` of `f32`.
+    if radix != 10 && matches!(l, Some("f32" | "f64")) {
+        f.push_str(l.take()?);
+    }
+
+    // remove digit separators
+    if radix != 10 || f.starts_with(|c: char| c.is_ascii_digit()) {
+        f.retain(|c| c != '_');
+    }
+    let n = format!("{sign}{f}");
+
+    let result = match l {
+        _ if f.is_empty() => None,
+        None if radix == 10 && f.contains(['.', 'e', 'E']) => {
+            n.parse::<f64>().ok().map(Token::Float).map(Ok)
+        }
+        None => match parse_integer!(n, radix, i128, |n| Token::Integer(n, None)) {
+            Some(Err(_)) => parse_integer!(n, radix, u128, |n| Token::LargeInteger(n, None)),
+            n => n,
+        },
+        Some("i8") => parse_integer!(n, radix, i8, Token::I8),
+        Some("u8") => parse_integer!(n, radix, u8, Token::U8),
+        Some("i16") => parse_integer!(n, radix, i16, Token::I16),
+        Some("u16") => parse_integer!(n, radix, u16, Token::U16),
+        Some("i32") => parse_integer!(n, radix, i32, Token::I32),
+        Some("u32") => parse_integer!(n, radix, u32, Token::U32),
+        Some("i64") => parse_integer!(n, radix, i64, Token::I64),
+        Some("u64") => parse_integer!(n, radix, u64, Token::U64),
+        Some("i128") => parse_integer!(n, radix, i128, Token::I128),
+        Some("u128") => parse_integer!(n, radix, u128, Token::U128),
+        Some("f32") => n.parse::<f32>().ok().map(Token::F32).map(Ok),
+        Some("f64") => n.parse::<f64>().ok().map(Token::F64).map(Ok),
         _ => None,
+    };
+
+    if result.is_some() || !is_number_like(s) {
+        result
+    } else {
+        Some(Err(format!("malformed number literal '{s}'.")))
     }
 }
 
+fn is_number_like(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let s = s.strip_prefix('.').unwrap_or(s);
+    s.starts_with(|c: char| c.is_ascii_digit())
+}
+
 fn parse_argument(s: &str) -> Option<Token> {
     s.strip_prefix('#')?
         .parse::<usize>()
@@ -182,29 +488,58 @@ fn parse_argument(s: &str) -> Option<Token> {
         .map(Token::Argument)
 }
 
-fn parse_string_literal(s: &str) -> String {
+fn parse_string_literal(s: &str) -> std::result::Result<String, String> {
     let mut result = String::new();
     let mut chars = s.chars();
     while let Some(c) = chars.next() {
-        if c == '\\' {
-            match chars.next() {
-                Some('n') => result.push('\n'),
-                Some('t') => result.push('\t'),
-                Some('r') => result.push('\r'),
-                Some('\\') => result.push('\\'),
-                Some('"') => result.push('"'),
-                Some('0') => result.push('\0'),
-                Some(c) => {
-                    result.push('\\');
-                    result.push(c);
-                }
-                None => result.push('\\'),
-            }
-        } else {
+        if c != '\\' {
             result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            Some('"') => result.push('"'),
+            Some('0') => result.push('\0'),
+            Some('x') => {
+                let n = chars.by_ref().take(2).collect::<String>();
+                let Some(c) = u8::from_str_radix(&n, 16)
+                    .ok()
+                    .filter(|_| n.len() == 2)
+                    .filter(|n| n.is_ascii())
+                else {
+                    return Err(format!(
+                        "invalid escape sequence \\x{n}; it must be \\x00 to \\x7F."
+                    ));
+                };
+                result.push(c as char);
+            }
+            Some('u') => {
+                let n = chars.as_str();
+                let Some(c) = n
+                    .strip_prefix('{')
+                    .and_then(|n| n.split_once('}'))
+                    .and_then(|(n, _)| u32::from_str_radix(n, 16).ok())
+                    .and_then(char::from_u32)
+                else {
+                    let n = match n.find('}') {
+                        Some(i) if n.starts_with('{') => &n[..=i],
+                        _ => "",
+                    };
+                    return Err(format!(
+                        "invalid escape sequence \\u{n}; it must be \\u{{...}} of a unicode scalar value."
+                    ));
+                };
+                result.push(c);
+                chars = n[n.find('}').unwrap() + 1..].chars();
+            }
+            Some(c) => return Err(format!("unknown escape sequence \\{c}.")),
+            None => return Err("unknown escape sequence \\ at the end.".to_string()),
         }
     }
-    result
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -278,6 +613,18 @@ mod test {
         insta::assert_yaml_snapshot!(tokens);
     }
 
+    #[test]
+    fn test_lex_map() {
+        let tokens = lex("#[\"a\" 1 'b #[]] '#[]").unwrap();
+        insta::assert_yaml_snapshot!(tokens);
+    }
+
+    #[test]
+    fn test_lex_typed_array_type() {
+        let tokens = lex("['[i32] '[[string]]. '[{}]]").unwrap();
+        insta::assert_yaml_snapshot!(tokens);
+    }
+
     #[test]
     fn test_lex_semicolon() {
         let tokens = lex("1 + 2; * 5").unwrap();
@@ -296,6 +643,127 @@ mod test {
         insta::assert_yaml_snapshot!(tokens);
     }
 
+    fn lex_string(code: &str) -> String {
+        match lex(code).unwrap().as_slice() {
+            [Token::String(n)] => n.clone(),
+            n => panic!("unexpected tokens {n:?}."),
+        }
+    }
+
+    #[test]
+    fn test_multi_line_string() {
+        assert_eq!(lex_string("\"hello\n  world\""), "hello\n  world");
+    }
+
+    #[test]
+    fn test_multi_line_string_tokens() {
+        let tokens = lex("\"a\nb\" !!\n1").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::String("a\nb".to_string()),
+                Token::Label("!!".to_string()),
+                Token::Integer(1, Some(Position(3, 1))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_string() {
+        assert_eq!(lex_string(r#"r"C:\path\n""#), r"C:\path\n");
+    }
+
+    #[test]
+    fn test_raw_string_with_hashes() {
+        assert_eq!(lex_string(r###"r##"say "#hi"#"##"###), r##"say "#hi"#"##);
+    }
+
+    #[test]
+    fn test_hex_and_unicode_escape() {
+        assert_eq!(lex_string(r#""\x41\u{1F600}\u{e9}""#), "A\u{1F600}\u{e9}");
+    }
+
+    #[test]
+    fn test_unknown_escape() {
+        assert_eq!(
+            lex_err(r#"1 "a\qb""#),
+            "error: 1:3: unknown escape sequence \\q."
+        );
+    }
+
+    #[test]
+    fn test_invalid_hex_escape() {
+        assert_eq!(
+            lex_err(r#""\xFF""#),
+            "error: 1:1: invalid escape sequence \\xFF; it must be \\x00 to \\x7F."
+        );
+        assert_eq!(
+            lex_err(r#""\x4""#),
+            "error: 1:1: invalid escape sequence \\x4; it must be \\x00 to \\x7F."
+        );
+    }
+
+    #[test]
+    fn test_invalid_unicode_escape() {
+        assert_eq!(
+            lex_err(r#""\u{D800}""#),
+            "error: 1:1: invalid escape sequence \\u{D800}; it must be \\u{...} of a unicode scalar value."
+        );
+        assert_eq!(
+            lex_err(r#""\u41""#),
+            "error: 1:1: invalid escape sequence \\u; it must be \\u{...} of a unicode scalar value."
+        );
+    }
+
+    #[test]
+    fn test_unterminated_raw_string() {
+        assert_eq!(
+            lex_err("r#\"abc\""),
+            "error: 1:1: unterminated raw string literal."
+        );
+    }
+
+    #[test]
+    fn test_comment_in_string() {
+        assert_eq!(lex_string("\"a -- b\" -- c"), "a -- b");
+    }
+
+    #[test]
+    fn test_block_comment() {
+        let tokens = lex("1 -{ comment\n -{ nested }- -- }-\n + 2").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Integer(1, Some(Position(1, 1))),
+                Token::Label("+".to_string()),
+                Token::Integer(2, Some(Position(3, 4))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        assert_eq!(
+            lex_err("1\n -{ -{ }-"),
+            "error: 2:2: unterminated block comment."
+        );
+    }
+
+    #[test]
+    fn test_doc_comment() {
+        let tokens = lex("--- first\n---second\n\n-- not doc\n---- not doc\n1 -> 'a.").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Doc("first\nsecond".to_string()),
+                Token::Integer(1, Some(Position(6, 1))),
+                Token::Label("->".to_string()),
+                Token::Symbol("a".to_string()),
+                Token::Dot,
+            ]
+        );
+    }
+
     #[test]
     fn test_comment() {
         let tokens = lex("-- head\n1 + 2. -- middle\n3 * 4 --tail").unwrap();
@@ -313,46 +781,280 @@ mod test {
     }
 
     #[test]
-    fn test_parse_2147483648_not_number() {
-        assert_eq!(parse_number("2147483648"), None);
-    }
-
-    #[test]
-    fn test_parse_2147483648u16_not_number() {
-        assert_eq!(parse_number("2147483648u16"), None);
+    fn test_parse_2147483648u16_overflow() {
+        assert!(matches!(parse_number("2147483648u16"), Some(Err(_))));
     }
 
     #[test]
     fn test_parse_2147483648u32_to_u32() {
-        assert_eq!(parse_number("2147483648u32"), Some(Token::U32(2147483648)));
+        assert_eq!(
+            parse_number("2147483648u32"),
+            Some(Ok(Token::U32(2147483648)))
+        );
     }
 
     #[test]
     fn test_parse_1f32_to_f32() {
-        assert_eq!(parse_number("1f32"), Some(Token::F32(1.0)));
+        assert_eq!(parse_number("1f32"), Some(Ok(Token::F32(1.0))));
     }
 
     #[test]
     fn test_parse_1_e_minus_2_f32_to_f32() {
-        assert_eq!(parse_number("1e-2f32"), Some(Token::F32(0.01)));
+        assert_eq!(parse_number("1e-2f32"), Some(Ok(Token::F32(0.01))));
     }
 
     #[test]
     fn test_parse_no_suffix_i32() {
         let n = Faker.fake::<i32>();
-        assert_eq!(parse_number(&format!("{n}")), Some(Token::I32(n)));
+        assert_eq!(
+            parse_number(&format!("{n}")),
+            Some(Ok(Token::Integer(n.into(), None)))
+        );
     }
 
     #[test]
     fn test_parse_u64() {
         let n = Faker.fake::<u64>();
-        assert_eq!(parse_number(&format!("{n}u64")), Some(Token::U64(n)));
+        assert_eq!(parse_number(&format!("{n}u64")), Some(Ok(Token::U64(n))));
     }
 
     #[test]
     fn test_parse_f32() {
         let n = Faker.fake::<f32>();
-        assert_eq!(parse_number(&format!("{n}f32")), Some(Token::F32(n)));
+        assert_eq!(parse_number(&format!("{n}f32")), Some(Ok(Token::F32(n))));
+    }
+
+    #[test]
+    fn test_parse_hexadecimal() {
+        assert_eq!(parse_number("0xFFu8"), Some(Ok(Token::U8(255))));
+        assert_eq!(parse_number("-0x80i8"), Some(Ok(Token::I8(-128))));
+    }
+
+    #[test]
+    fn test_parse_hexadecimal_f_not_suffix() {
+        assert_eq!(
+            parse_number("0x1f32"),
+            Some(Ok(Token::Integer(0x1f32, None)))
+        );
+    }
+
+    #[test]
+    fn test_parse_octal() {
+        assert_eq!(parse_number("0o755"), Some(Ok(Token::Integer(0o755, None))));
+    }
+
+    #[test]
+    fn test_parse_binary() {
+        assert_eq!(
+            parse_number("0b1010_1010u8"),
+            Some(Ok(Token::U8(0b1010_1010)))
+        );
+    }
+
+    #[test]
+    fn test_parse_digit_separator() {
+        assert_eq!(
+            parse_number("1_000_000i64"),
+            Some(Ok(Token::I64(1_000_000)))
+        );
+        assert_eq!(parse_number("1_000.5_f64"), Some(Ok(Token::F64(1_000.5))));
+    }
+
+    #[test]
+    fn test_parse_underscore_not_number() {
+        assert_eq!(parse_number("_1"), None);
+    }
+
+    #[test]
+    fn test_parse_0x100u8_overflow() {
+        assert!(matches!(parse_number("0x100u8"), Some(Err(_))));
+    }
+
+    fn lex_err(code: &str) -> String {
+        lex(code).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_parse_no_suffix_wider_than_i32() {
+        assert_eq!(
+            parse_number("2147483648"),
+            Some(Ok(Token::Integer(2147483648, None)))
+        );
+        assert_eq!(
+            parse_number(&format!("{}", u128::MAX)),
+            Some(Ok(Token::LargeInteger(u128::MAX, None)))
+        );
+    }
+
+    #[test]
+    fn test_lex_no_suffix_overflow() {
+        assert_eq!(
+            lex_err("340282366920938463463374607431768211456"),
+            "error: 1:1: the literal is out of range of u128."
+        );
+    }
+
+    #[test]
+    fn test_lex_negative_unsigned() {
+        assert_eq!(
+            lex_err("-1u8"),
+            "error: 1:1: the literal is out of range of u8."
+        );
+        assert_eq!(
+            lex_err("-0x10u32"),
+            "error: 1:1: the literal is out of range of u32."
+        );
+        assert_eq!(parse_number("-0u8"), Some(Ok(Token::U8(0))));
+        assert_eq!(
+            lex_err("-1xu8"),
+            "error: 1:1: malformed number literal '-1xu8'."
+        );
+    }
+
+    #[test]
+    fn test_parse_no_suffix_float() {
+        assert_eq!(parse_number("1.5"), Some(Ok(Token::Float(1.5))));
+        assert_eq!(parse_number(".5"), Some(Ok(Token::Float(0.5))));
+        assert_eq!(parse_number("3."), Some(Ok(Token::Float(3.0))));
+    }
+
+    #[test]
+    fn test_parse_no_suffix_scientific_notation() {
+        assert_eq!(parse_number("1e5"), Some(Ok(Token::Float(1e5))));
+        assert_eq!(parse_number("-2.5E-3"), Some(Ok(Token::Float(-2.5e-3))));
+    }
+
+    #[test]
+    fn test_parse_hexadecimal_e_not_exponent() {
+        assert_eq!(parse_number("0x1e5"), Some(Ok(Token::Integer(0x1e5, None))));
+    }
+
+    #[test]
+    fn test_lex_malformed_number() {
+        assert_eq!(
+            lex_err("12abc"),
+            "error: 1:1: malformed number literal '12abc'."
+        );
+        assert_eq!(lex_err("0x"), "error: 1:1: malformed number literal '0x'.");
+        assert_eq!(
+            lex_err("0b102"),
+            "error: 1:1: malformed number literal '0b102'."
+        );
+        assert_eq!(
+            lex_err("1i7"),
+            "error: 1:1: malformed number literal '1i7'."
+        );
+        assert_eq!(
+            lex_err(".5x"),
+            "error: 1:1: malformed number literal '.5x'."
+        );
+        assert_eq!(
+            lex_err("1.2.3"),
+            "error: 1:1: malformed number literal '1.2.3'."
+        );
+    }
+
+    #[test]
+    fn test_lex_unterminated_string() {
+        assert_eq!(lex_err("\"abc"), "error: 1:1: unterminated string literal.");
+        assert_eq!(
+            lex_err("1.\n  \"abc !!"),
+            "error: 2:3: unterminated string literal."
+        );
+        assert_eq!(
+            lex_err(r#""abc\""#),
+            "error: 1:1: unterminated string literal."
+        );
+        assert_eq!(lex_err("\""), "error: 1:1: unterminated string literal.");
+    }
+
+    #[test]
+    fn test_lex_stray_quote() {
+        assert_eq!(lex_err("ab\"c"), "error: 1:1: stray '\"' found in 'ab\"c'.");
+        assert_eq!(
+            lex_err("1 \"a\" \"b\"c\""),
+            "error: 1:10: stray '\"' found in 'c\"'."
+        );
+    }
+
+    #[test]
+    fn test_lex_empty_symbol() {
+        assert_eq!(lex_err("1 -> '"), "error: 1:6: symbol name is empty.");
+    }
+
+    #[test]
+    fn test_lex_string_followed_by_sign() {
+        let tokens = lex(r#"["a" "b"]."#).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LBracket,
+                Token::String("a".to_string()),
+                Token::String("b".to_string()),
+                Token::RBracket,
+                Token::Dot,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_string_followed_by_verb() {
+        let tokens = lex(r#""abc"!! "a"$> "b""#).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::String("abc".to_string()),
+                Token::Label("!!".to_string()),
+                Token::String("a".to_string()),
+                Token::Label("$>".to_string()),
+                Token::String("b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_integer_position() {
+        let tokens = lex("1 +\n  2147483648").unwrap();
+        assert!(matches!(
+            tokens[2],
+            Token::Integer(2147483648, Some(Position(2, 3)))
+        ));
+        assert_eq!(
+            tokens[2].out_of_range(&"i32").0,
+            "error: 2:3: the literal 2147483648 is out of range of i32."
+        );
+        assert_eq!(
+            Token::from("2147483648").out_of_range(&"i32").0,
+            "error: the literal 2147483648 is out of range of i32."
+        );
+    }
+
+    #[test]
+    fn test_lex_range() {
+        let tokens = lex("1 .. 5. 1 ../ 5 2.").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Integer(1, Some(Position(1, 1))),
+                Token::Label("..".to_string()),
+                Token::Integer(5, Some(Position(1, 6))),
+                Token::Dot,
+                Token::Integer(1, Some(Position(1, 9))),
+                Token::Label("../".to_string()),
+                Token::Integer(5, Some(Position(1, 15))),
+                Token::Integer(2, Some(Position(1, 17))),
+                Token::Dot,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_overflow_with_position() {
+        let e = lex("1 + 2.\n3 +  256u8").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "error: 2:6: the literal is out of range of u8."
+        );
     }
 
     #[test]
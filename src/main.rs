@@ -1,3 +1,5 @@
+mod doc;
+mod error;
 mod evaluator;
 mod lexer;
 mod repl;
@@ -17,10 +19,54 @@ fn main() {
         return;
     }
 
-    let args = cmd_args.split_off(1);
-    let path = cmd_args.pop().unwrap();
-    if let Err(e) = script::run(path, args) {
-        eprintln!("{e}");
-        process::exit(1);
+    let result = if cmd_args[0] == "--doc" {
+        doc::run(cmd_args.split_off(1))
+    } else {
+        parse_cmd_args(cmd_args).and_then(|(src, args)| script::run(src, args))
+    };
+    if let Err(e) = result {
+        if !e.is::<error::Exit>() {
+            eprintln!("{e}");
+        }
+        process::exit(script::exit_code(e.as_ref()));
     }
 }
+
+/// A function to split command line arguments into a script source and script arguments.
+///
+/// Interpreter flags are accepted only before the script source:
+///
+/// * `-e <code>` - evaluates `code` directly.
+/// * `-` - reads the script from standard input.
+/// * `--doc` - outputs documents instead of running a script, which is handled in `main`.
+/// * `--` - ends interpreter flags; the next argument is treated as a path.
+///
+/// A `--` placed right after the script source is dropped,
+/// so that script arguments starting with `-` can be passed explicitly.
+fn parse_cmd_args(cmd_args: Vec<String>) -> RResult<(script::Source, Vec<String>)> {
+    let mut cmd_args = cmd_args.into_iter();
+
+    let src = match cmd_args.next().as_deref() {
+        Some("-e") => {
+            let Some(code) = cmd_args.next() else {
+                return Err("error: -e requires code to evaluate.".into());
+            };
+            script::Source::Inline(code)
+        }
+        Some("-") => script::Source::Stdin,
+        Some("--") => {
+            let Some(path) = cmd_args.next() else {
+                return Err("error: no script path passed after --.".into());
+            };
+            script::Source::Path(path)
+        }
+        Some(n) if n.starts_with('-') => return Err(format!("error: unknown option {n}.").into()),
+        Some(n) => script::Source::Path(n.to_string()),
+        None => return Err("error: no script passed.".into()),
+    };
+
+    let mut args = cmd_args.peekable();
+    args.next_if(|n| n == "--");
+
+    Ok((src, args.collect()))
+}
@@ -1,22 +1,31 @@
 use crate::{
     RResult,
+    error::Exit,
     evaluator::{self, EnterLazyParams, Environment},
     lexer,
 };
-use std::io::{self, Write};
+use std::{
+    io::{self, Write},
+    process,
+};
 
 pub fn run() {
     let mut env = Environment::default();
     let params = EnterLazyParams {
         slf: None,
         args: Some(vec![]),
+        vars: Vec::new(),
+        captures: None,
     };
     env.prepare_block_scope(params);
     loop {
         match run_inner(&mut env) {
             Ok(true) => (),
             Ok(false) => break,
-            Err(n) => println!("{n}"),
+            Err(n) => match n.downcast_ref() {
+                Some(Exit(n)) => process::exit(*n),
+                None => println!("{n}"),
+            },
         }
     }
 }
@@ -36,6 +45,16 @@ fn run_inner(env: &mut Environment) -> RResult<bool> {
         return Ok(false);
     }
 
+    // show document?
+    if let Some(n) = input.strip_prefix("/doc ") {
+        let n = n.trim();
+        match env.get_doc(n) {
+            Some(d) => println!("{d}"),
+            None => println!("error: no document for {n}."),
+        }
+        return Ok(true);
+    }
+
     // eval
     let mut tokens = lexer::lex(input)?;
     tokens.reverse();
@@ -1,16 +1,45 @@
 use crate::{
     RResult,
+    error::{Exit, LexError, TypeError},
     evaluator::{self, EnterLazyParams, Environment},
     lexer,
 };
-use std::fs;
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read},
+};
+
+pub enum Source {
+    Path(String),
+    Stdin,
+    Inline(String),
+}
+
+impl Source {
+    fn read(self) -> RResult<String> {
+        match self {
+            Self::Path(path) => {
+                fs::read_to_string(&path).map_err(|e| format!("error: {path}: {e}").into())
+            }
+            Self::Stdin => {
+                let mut content = String::new();
+                io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(|e| format!("error: stdin: {e}"))?;
+                Ok(content)
+            }
+            Self::Inline(code) => Ok(code),
+        }
+    }
+}
 
-pub fn run(path: String, args: Vec<String>) -> RResult<()> {
-    // read file
-    let content = fs::read_to_string(&path).map_err(|e| format!("error: {path}: {e}"))?;
+pub fn run(src: Source, args: Vec<String>) -> RResult<()> {
+    // read source
+    let content = src.read()?;
 
     // lex
-    let mut tokens = lexer::lex(&content)?;
+    let mut tokens = lexer::lex(strip_shebang(&content))?;
 
     // setup
     let mut env = Environment::default();
@@ -18,6 +47,8 @@ pub fn run(path: String, args: Vec<String>) -> RResult<()> {
     let params = EnterLazyParams {
         slf: None,
         args: Some(args),
+        vars: Vec::new(),
+        captures: None,
     };
     tokens.reverse();
 
@@ -27,3 +58,34 @@ pub fn run(path: String, args: Vec<String>) -> RResult<()> {
     // finish
     Ok(())
 }
+
+/// A function to map an error returned from `run` to the process exit code.
+///
+/// | Error | Exit code |
+/// | --- | --- |
+/// | `Exit(n)` | `n` |
+/// | `LexError` | 2 |
+/// | `TypeError` | 3 |
+/// | others | 1 |
+pub fn exit_code(e: &(dyn Error + 'static)) -> i32 {
+    if let Some(Exit(n)) = e.downcast_ref() {
+        *n
+    } else if e.is::<LexError>() {
+        2
+    } else if e.is::<TypeError>() {
+        3
+    } else {
+        1
+    }
+}
+
+/// A function to skip a leading `#!` line so that scripts can be made executable.
+///
+/// NOTE: The line break is kept so that the following lines aren't shifted.
+pub fn strip_shebang(content: &str) -> &str {
+    if content.starts_with("#!") {
+        content.find('\n').map(|n| &content[n..]).unwrap_or("")
+    } else {
+        content
+    }
+}
@@ -24,6 +24,11 @@ fn test_float() {
     run("1.2f32\n/exit", ">> 1.2 (f32)\n>> ");
 }
 
+#[test]
+fn test_float_without_suffix() {
+    run("1.5\n/exit", ">> 1.5 (f64)\n>> ");
+}
+
 #[test]
 fn test_expression() {
     run("2 * 3 + 4\n/exit", ">> 14 (i32)\n>> ");
@@ -98,3 +103,83 @@ fn test_restrict_redefine_variable_upper_scope() {
 fn test_print() {
     run("1 !\n2 !!\n/exit\n", ">> 11 (i32)\n>> 2\n2 (i32)\n>> ")
 }
+
+#[test]
+fn test_typed_array() {
+    run(
+        "[1 2]\n[1 'a]\n[[1] [2]]\n{} : ['[i32]]\n/exit\n",
+        ">> [1 2] ([i32])\n>> [1 a]\n>> [[1] [2]] ([[i32]])\n>> @[[i32]]\n>> ",
+    )
+}
+
+#[test]
+fn test_generic_function_error() {
+    run(
+        "{ #0 } : ['$T '$T], -> 'f.\nf @ 1 \"a\"\n/exit\n",
+        ">> ()\n>> error: @ on @[$T $T] expects $T = i32 for #1 but got string.\n>> ",
+    )
+}
+
+#[test]
+fn test_partial_application_signature() {
+    run(
+        "{ #0 + #1 } : ['i32 'i32], -> 'add.\nadd @< [1]\n({ [#0 #1] } : ['$T '$T]) @< [\"a\"]\nadd @> ({ #0 } : ['i32])\n/exit\n",
+        ">> ()\n>> @[i32]\n>> @[string]\n>> @[i32 i32]\n>> ",
+    )
+}
+
+#[test]
+fn test_optional_parameter_signature() {
+    run(
+        "{ } : ['string #['i32 1] '_*]\n{ } : ['i32 #['i32 \"a\"]]\n/exit\n",
+        ">> @[string i32? _*]\n>> error: the default value must be i32 but got string.\n>> ",
+    )
+}
+
+#[test]
+fn test_doc_variable() {
+    run(
+        "--- twelve.\n12 => 'a.\n/doc a\n/exit",
+        ">> ()\n>> ()\n>> twelve.\n>> ",
+    );
+}
+
+#[test]
+fn test_doc_function() {
+    run(
+        "--- increments.\n--- returns i32.\n{ ## + 1 } : [], => 'i32:incr.\n/doc i32:incr\n/exit",
+        ">> ()\n>> ()\n>> ()\n>> increments.\nreturns i32.\n>> ",
+    );
+}
+
+#[test]
+fn test_doc_user_type() {
+    run(
+        "--- a point.\n[':x 'i32 '::y 'i32] |> 'point.\n/doc point\n/exit",
+        ">> ()\n>> ()\n>> a point.\n:x i32\n::y i32\n>> ",
+    );
+}
+
+#[test]
+fn test_doc_interface() {
+    run(
+        "--- sized.\n['# [] \"length\" '@ ['i32]] |& 'sized.\n/doc sized\n/exit",
+        ">> ()\n>> ()\n>> sized.\n# @[] -- length\n@ @[i32]\n>> ",
+    );
+}
+
+#[test]
+fn test_doc_overloads() {
+    run(
+        "--- by a scalar.\n{ ## * #0 } : ['i32], -> 'i32:scale.\n{ ## } : ['string], -> 'i32:scale.\n/doc i32:scale\n/exit",
+        ">> ()\n>> ()\n>> ()\n>> @[i32]\nby a scalar.\n@[string]\n>> ",
+    );
+}
+
+#[test]
+fn test_doc_only_following_sentence() {
+    run(
+        "--- doc. -{ block }-\n1 + 2. 3 => 'a.\n/doc a\n/exit",
+        ">> ()\n>> ()\n>> error: no document for a.\n>> ",
+    );
+}
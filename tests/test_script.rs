@@ -18,6 +18,42 @@ fn run_with(args: &[&str], output: &'static str) {
         .stdout(output);
 }
 
+fn run_stdin(args: &[&str], input: &'static str, output: &'static str) {
+    Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .args(args)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(output);
+}
+
+fn run_wrong_with(args: &[&str]) {
+    Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .args(args)
+        .assert()
+        .failure();
+}
+
+fn run_code(path: &'static str, output: &'static str, code: i32) {
+    Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .args([path])
+        .assert()
+        .code(code)
+        .stdout(output);
+}
+
+fn run_code_with(args: &[&str], output: &'static str, code: i32) {
+    Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .args(args)
+        .assert()
+        .code(code)
+        .stdout(output);
+}
+
 fn run_wrong(path: &'static str) {
     Command::cargo_bin(env!("CARGO_PKG_NAME"))
         .unwrap()
@@ -46,6 +82,253 @@ fn test_implicit_comma() {
     run("tests/scripts/implicit-comma.fuz", "300\n");
 }
 
+#[test]
+fn test_string_literal() {
+    run(
+        "tests/scripts/string-literal.fuz",
+        "multi\nline\nraw\\n\nraw \"quoted\"\nA\u{3042}\na -- not a comment\n",
+    );
+}
+
+#[test]
+fn test_doc() {
+    run_with(
+        &["--doc", "tests/scripts/doc.fuz"],
+        "# point\n\n## General\n\nA point on a plane.\n\n\
+         | Field | Type | Visibility | Description |\n\
+         | --- | --- | --- | --- |\n\
+         | `x` | `'i32` | public | the horizontal position |\n\
+         | `y` | `'i32` | private |  |\n\n\
+         ## Interfaces\n\n- `movable`\n\n\
+         ## Functions\n\n\
+         ### `move`: `@['i32]`\n\nMoves the point horizontally.\n\n\
+         - Visibility: public\n- Mutability: mutable\n\n\
+         ### `height`: `@[]`\n\n\
+         - Visibility: private\n- Mutability: immutable\n\n\
+         # i32\n\n## Functions\n\n\
+         ### `twice`: `@[['i32]]`\n\nApplies a function twice.\n\n\
+         - Visibility: public\n- Mutability: immutable\n\n\
+         # shape\n\n## General\n\nA shape drawn at a point.\n\n\
+         | Variant | Field | Type | Visibility | Description |\n\
+         | --- | --- | --- | --- | --- |\n\
+         | `circle` | | | | a round shape |\n\
+         | | `radius` | `'f64` | public | the distance from the center |\n\
+         | `dot` | | | |  |\n\n\
+         # movable\n\n## General\n\nSomething movable.\n\n\
+         | Verb | Types | Description |\n\
+         | --- | --- | --- |\n\
+         | `move` | `@['i32]` | moves by the distance |\n",
+    );
+    run("tests/scripts/doc.fuz", "4\n18\ndot[]\n");
+}
+
+#[test]
+fn test_script_named_doc() {
+    let dir = std::env::temp_dir().join("fuzzy-test-script-named-doc");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("doc"), "\"not a document\" !!").unwrap();
+    Command::cargo_bin(env!("CARGO_PKG_NAME"))
+        .unwrap()
+        .current_dir(&dir)
+        .args(["doc"])
+        .assert()
+        .success()
+        .stdout("not a document\n");
+}
+
+#[test]
+fn test_doc_builtins_in_sync() {
+    let dir = std::env::temp_dir().join("fuzzy-test-doc-builtins");
+    std::fs::create_dir_all(&dir).unwrap();
+    run_with(&["--doc", "--builtins", dir.to_str().unwrap()], "");
+    for n in std::fs::read_dir("docs/types").unwrap() {
+        let path = n.unwrap().path();
+        let generated = std::fs::read_to_string(dir.join(path.file_name().unwrap())).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            generated,
+            written,
+            "{} is out of date; run `fuzzy --doc --builtins docs/types`.",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn test_map() {
+    run_code(
+        "tests/scripts/map.fuz",
+        "#[a 1 b 2 3 x]\n3\n1\nnone[]\nsome[1]\n#[a 1 b 2 3 x c [1 2]]\n#[a 1 3 x]\nT\n()\n\
+         [a b 3]\n[1 2 x]\n[[a 1] [b 2] [3 x]]\n#[a 0 b 2 3 x d #[]]\nT\n1\n",
+        1,
+    );
+}
+
+#[test]
+fn test_typed_array() {
+    run(
+        "tests/scripts/typed-array.fuz",
+        "3\n0\n[[a] []]\n[1.5 2.5]\n",
+    );
+}
+
+#[test]
+fn test_constructor() {
+    run(
+        "tests/scripts/constructor.fuz",
+        "[:age 0 ::id 0 :name ann]\n[:age 30 ::id 0 :name anonymous]\n[:age 0 ::id 3 :name bob]\n\
+         err[200 (u8) is invalid for field age of person.]\n",
+    );
+}
+
+#[test]
+fn test_field_update() {
+    run(
+        "tests/scripts/field-update.fuz",
+        "[::secret s :x 5 :y 2]\n[::secret s :x 1 :y 2]\n[::secret s :x 7 :y 8]\n\
+         [::secret s :x 1 :y 10]\n[::secret t :x 1 :y 10]\ncircle[:radius 2]\n",
+    );
+}
+
+#[test]
+fn test_option() {
+    run(
+        "tests/scripts/option.fuz",
+        "()\n()\nsome[()]\nnone[]\n()\n4\n0\nsome[8]\n\
+         ok[12]\n-1\nsome[cannot parse \"foo\" as i32.]\nT\nok[2]\n()\n\
+         ok[42]\nerr[cannot parse \"x\" as i32.]\nsome[2]\nnone[]\nT\n()\nT\n",
+    );
+}
+
+#[test]
+fn test_pattern_match() {
+    run(
+        "tests/scripts/pattern-match.fuz",
+        "4\nzero\nnegative\npositive\ngreeting\ntext\nfoo symbol\nends with 9\nsomething\n\
+         square\n1\n7\nopt\n",
+    );
+}
+
+#[test]
+fn test_generic_function() {
+    run(
+        "tests/scripts/generic-function.fuz",
+        "2\nb\n5\n3\nx\n6\na\n3\n3\n",
+    );
+}
+
+#[test]
+fn test_interface() {
+    run(
+        "tests/scripts/interface.fuz",
+        "18\n6\n4\n5\n3\n[:shape [:side 2]]\n3\n",
+    );
+}
+
+#[test]
+fn test_overload() {
+    run(
+        "tests/scripts/overload.fuz",
+        "[:x 2 :y 4]\n[:x 1.5 :y 2.5]\n[:x 3 :y 6]\n[:x 1 :y 6]\n[:x 1 :y 2]\n[:x 1 :y 6]\n",
+    );
+}
+
+#[test]
+fn test_closure() {
+    run(
+        "tests/scripts/closure.fuz",
+        "1\n2\n1\n3\n15\n30\n5\n7\nT\nT\n",
+    );
+}
+
+#[test]
+fn test_partial_application() {
+    run(
+        "tests/scripts/partial-application.fuz",
+        "3\n3\n[a b]\n6\n8\n4\n@[i32]\n@[i32 i32]\n",
+    );
+}
+
+#[test]
+fn test_wrong_partial_application() {
+    run_wrong("tests/scripts/wrong-partial-application.fuz");
+}
+
+#[test]
+fn test_optional_parameter() {
+    run(
+        "tests/scripts/optional-parameter.fuz",
+        "Hello, world\nGoodbye, moon\nvalues: [1 a b]\nnothing: []\n3\n2\n0\n\
+         [0 1 []]\n[0 2 []]\n[0 2 [3 4]]\n[1 d]\n[1 x]\n2\n",
+    );
+}
+
+#[test]
+fn test_wrong_optional_parameter() {
+    run_wrong("tests/scripts/wrong-optional-parameter.fuz");
+}
+
+#[test]
+fn test_higher_order_array() {
+    run(
+        "tests/scripts/higher-order-array.fuz",
+        "[1 4 9 16 25]\n[1 3 5]\n15\n[10 20 30 40 50]\n15\nT\n()\nsome[3]\n0\n[1 2 3]\n\
+         [10 20 30 40 50]\n[3 5 7 9 11]\n",
+    );
+}
+
+#[test]
+fn test_wrong_higher_order_array() {
+    run_wrong("tests/scripts/wrong-higher-order-array.fuz");
+}
+
+#[test]
+fn test_array_sort() {
+    run(
+        "tests/scripts/array-sort.fuz",
+        "[1 2 3 4 5]\n[a b c]\n[[1] [1 3] [2 1]]\n[alice dave carol bob]\n[carol bob alice dave]\n\
+         [3 2 1]\nok[2]\nerr[2]\nsome[2]\n()\n[3 1 2]\n[1 2 3 4 5]\n[1 3]\n[1 3]\nT\nT\n",
+    );
+}
+
+#[test]
+fn test_wrong_compare() {
+    run_wrong("tests/scripts/wrong-compare.fuz");
+}
+
+#[test]
+fn test_slice_range() {
+    run(
+        "tests/scripts/slice-range.fuz",
+        "[2 3]\n[3 4]\n[1 2 3]\n[]\nél\nllo\n6\n3\n[1 2 3 4]\nabcd\n[1 2 1 2 1 2]\n[]\n\
+         [1 2 3 4]\n[]\n[0 3 6 9]\n[5 3 1]\n[250 253]\n55\n",
+    );
+}
+
+#[test]
+fn test_range_length() {
+    run_with(&["-e", "0 .. 16777216, # !!"], "16777216\n");
+    run_with(&["-e", "5 ../ 0 -20, !!"], "[5]\n");
+    run_with(&["-e", "0 ../ 5 -1, !!"], "[]\n");
+    run_code_with(&["-e", "1 .. 2147483647"], "", 1);
+    run_code_with(&["-e", "0 ../ 2147483647 1"], "", 1);
+    run_code_with(&["-e", "2147483647 ../ -2147483648 -1"], "", 1);
+}
+
+#[test]
+fn test_wrong_slice() {
+    run_wrong("tests/scripts/wrong-slice.fuz");
+}
+
+#[test]
+fn test_sum_type() {
+    run(
+        "tests/scripts/sum-type.fuz",
+        "circle[:radius 2]\nrect[:height 3.5 :width 2]\ndot[]\n12\n7\n0\n\
+         square\nsquare\n2\nT\n()\n()\n3\n1\n",
+    );
+}
+
 #[test]
 fn test_symbol_value() {
     run("tests/scripts/symbol-value.fuz", "1\n3\n");
@@ -109,6 +392,83 @@ fn test_command_line_argument() {
     );
 }
 
+#[test]
+fn test_inline_code() {
+    run_with(&["-e", "1 + 2, !! #0 !!", "foo"], "3\nfoo\n");
+}
+
+#[test]
+fn test_inline_code_with_double_dash() {
+    run_with(&["-e", "#0 !! #1 !!", "--", "-e", "--"], "-e\n--\n");
+}
+
+#[test]
+fn test_stdin() {
+    run_stdin(&["-", "foo"], "\"Hello, \" ! #0 !!", "Hello, foo\n");
+}
+
+#[test]
+fn test_path_after_double_dash() {
+    run_with(
+        &["--", "tests/scripts/hello-world.fuz", "--", "-x"],
+        "Hello, world!\n",
+    );
+}
+
+#[test]
+fn test_shebang() {
+    run("tests/scripts/shebang.fuz", "Hello, shebang!\n");
+    run_with(
+        &["--doc", "tests/scripts/shebang.fuz"],
+        "# string\n\n## Functions\n\n\
+         ### `greet`: `@[]`\n\nPrints a greeting.\n\n\
+         - Visibility: public\n- Mutability: mutable\n",
+    );
+}
+
+#[test]
+fn test_typed_argument() {
+    run_with(
+        &[
+            "tests/scripts/typed-argument.fuz",
+            "world",
+            "2",
+            "--loud",
+            "--suffix=?",
+        ],
+        "Hello, world?!!\nHello, world?!!\n3\n",
+    );
+}
+
+#[test]
+fn test_typed_argument_after_double_dash() {
+    run_with(
+        &["tests/scripts/typed-argument.fuz", "--", "--", "--x", "1"],
+        "Hello, --x\n2\n",
+    );
+}
+
+#[test]
+fn test_typed_argument_help() {
+    run_with(
+        &["tests/scripts/typed-argument.fuz", "--help"],
+        "usage: greet [options] <name> <times>\n\narguments:\n  <name>   string  the name to greet\n  <times>  u8\n\noptions:\n  --loud             greets loudly\n  --suffix <string>\n  -h, --help         shows this message.\n",
+    );
+}
+
+#[test]
+fn test_string_followed_by_verb() {
+    run_with(&["-e", "\"abc\"!!"], "abc\n");
+}
+
+#[test]
+fn test_parse_string() {
+    run_with(
+        &["-e", "\"12\" : 'i64, + 1i64, !! \"2.5\" : 'f32, !!"],
+        "13\n2.5\n",
+    );
+}
+
 #[test]
 fn test_while_loop() {
     run(
@@ -140,7 +500,27 @@ fn test_extend_builtin_type() {
 
 #[test]
 fn test_define_type() {
-    run("tests/scripts/define-type.fuz", "12\n55\n35\n");
+    run("tests/scripts/define-type.fuz", "12\n55\n35\n20\n");
+}
+
+#[test]
+fn test_number_literal() {
+    run_with(
+        &[
+            "-e",
+            "0xFFu8 !! 0o755 !! 0b1010_1010u8 !! 1_000_000i64 !! -0x80i8 !!",
+        ],
+        "255\n493\n170\n1000000\n-128\n",
+    );
+}
+
+#[test]
+fn test_numeric_literal_inference() {
+    run(
+        "tests/scripts/numeric-literal.fuz",
+        "3.75\n1000\n11\n50\n250\n3\n6000000000\n\
+         300000000000000000000000000000000000001\n42\n",
+    );
 }
 
 #[test]
@@ -151,11 +531,61 @@ fn test_string() {
     );
 }
 
+#[test]
+fn test_exit() {
+    run_code("tests/scripts/exit.fuz", "before exit\n", 3);
+}
+
+#[test]
+fn test_exit_code_out_of_range() {
+    run_code_with(&["-e", "255 !<"], "", 255);
+    run_code_with(&["-e", "256 !<"], "", 1);
+    run_code_with(&["-e", "-1 !<"], "", 1);
+}
+
+#[test]
+fn test_exit_in_try_cast() {
+    run_code_with(
+        &["-e", "[':x 'i32 '@ { 7 !< }] |> 'p. [':x 1] :? 'p, !!"],
+        "",
+        7,
+    );
+}
+
+#[test]
+fn test_exit_code_type_error() {
+    run_code("tests/scripts/wrong-add.fuz", "", 3);
+    run_code_with(&["-e", "[1] %@ 1"], "", 3);
+    run_code_with(&["-e", "[1] %@ ({ #0 } : ['i32 'i32])"], "", 3);
+    run_code_with(&["-e", "({ #0 } : ['i32]) @> 1"], "", 3);
+}
+
+#[test]
+fn test_exit_code_lex_error() {
+    run_code("tests/scripts/wrong-overflow.fuz", "", 2);
+}
+
+#[test]
+fn test_exit_code_runtime_error() {
+    run_code("tests/scripts/wrong-toplevel-self.fuz", "", 1);
+}
+
 #[test]
 fn test_type_missmatched_addition() {
     run_wrong("tests/scripts/wrong-add.fuz");
 }
 
+#[test]
+fn test_wrong_literal_out_of_range() {
+    run_code("tests/scripts/wrong-literal-out-of-range.fuz", "", 3);
+    run_code_with(&["-e", "2147483648 !!"], "", 3);
+    run_code_with(
+        &["-e", "1i64 + 300000000000000000000000000000000000000"],
+        "",
+        3,
+    );
+}
+
 #[test]
 fn test_undefined_value_of_symbol_subject() {
     run_wrong("tests/scripts/wrong-symbol-value-subject.fuz");
@@ -181,6 +611,11 @@ fn test_wrong_toplevel_self() {
     run_wrong("tests/scripts/wrong-toplevel-self.fuz");
 }
 
+#[test]
+fn test_wrong_mutate_captured_constant() {
+    run_wrong("tests/scripts/wrong-mutate-captured-constant.fuz");
+}
+
 #[test]
 fn test_wrong_redefine_self() {
     run_wrong("tests/scripts/wrong-redefine-self.fuz");
@@ -206,6 +641,150 @@ fn test_wrong_private_access() {
     run_wrong("tests/scripts/wrong-private-access.fuz");
 }
 
+#[test]
+fn test_wrong_unknown_option() {
+    run_wrong_with(&["-x", "tests/scripts/hello-world.fuz"]);
+}
+
+#[test]
+fn test_wrong_inline_code_missing() {
+    run_wrong_with(&["-e"]);
+}
+
+#[test]
+fn test_wrong_typed_argument() {
+    run_wrong_with(&["tests/scripts/typed-argument.fuz", "world", "x"]);
+    run_wrong_with(&["tests/scripts/typed-argument.fuz", "world"]);
+    run_wrong_with(&["tests/scripts/typed-argument.fuz", "world", "1", "2"]);
+    run_wrong_with(&["tests/scripts/typed-argument.fuz", "world", "1", "--x"]);
+    run_wrong_with(&["tests/scripts/typed-argument.fuz", "world", "1", "--suffix"]);
+    run_wrong_with(&[
+        "tests/scripts/typed-argument.fuz",
+        "world",
+        "1",
+        "--suffix=!",
+        "--suffix=?",
+    ]);
+    run_wrong_with(&[
+        "tests/scripts/typed-argument.fuz",
+        "world",
+        "1",
+        "--loud",
+        "--loud",
+    ]);
+}
+
+#[test]
+fn test_wrong_parse_string() {
+    run_wrong_with(&["-e", "\"x\" : 'i32"]);
+}
+
+#[test]
+fn test_wrong_map_literal() {
+    run_wrong_with(&["-e", "#['a 1 'b]"]);
+    run_wrong_with(&["-e", "#[1.5 'a]"]);
+}
+
+#[test]
+fn test_wrong_constructor() {
+    run_wrong_with(&[
+        "-e",
+        "[':x 'i32 '? { #0 >= 0 } : ['i32]] |> 'p. [':x 1] : 'p, :< ':x -1",
+    ]);
+    run_wrong_with(&["-e", "[':x 'i32 '= 1.5] |> 'p"]);
+    run_wrong_with(&["-e", "[':x 'i32 '@ { 1 }] |> 'p. [':x 1] : 'p"]);
+    run_wrong_with(&["-e", "[':x 'i32 '@ { \"bad\" !- }] |> 'p. [':x 1] : 'p"]);
+    run_wrong_with(&["-e", "[':x 'i32] |> 'p. [':y 1] : 'p"]);
+    run_wrong_with(&["-e", "[':x 'i32 \"doc\" ':y 'i32] |> 'p"]);
+    run_code_with(&["-e", "[':x 'i32] |> 'p. [':x] : 'p, !!"], "", 1);
+}
+
+#[test]
+fn test_wrong_field_update() {
+    run_wrong_with(&["-e", "[':x 'i32] |> 'p. [':x 1] : 'p, :< ':x 1.5"]);
+    run_wrong_with(&["-e", "[':x 'i32] |> 'p. [':x 1] : 'p, :< ':z 1"]);
+    run_wrong_with(&[
+        "-e",
+        "[':x 'i32 '::y 'i32] |> 'p. [':x 1 '::y 2] : 'p, :< '::y 1",
+    ]);
+    run_wrong_with(&["-e", "[':x 'i32] |> 'p. [':x 1] : 'p, => 'v. 'v :< ':x 2"]);
+}
+
+#[test]
+fn test_wrong_pattern() {
+    run_wrong_with(&["-e", "1 ?= [2 { 3 }]"]);
+    run_wrong_with(&["-e", "[1] ?= [['..$a '..$b] { 1 }]"]);
+    run_wrong_with(&["-e", "1 ?= ['$x]"]);
+    run_wrong_with(&[
+        "-e",
+        "[':x 'i32 '::y 'i32] |> 'p. [':x 1 '::y 2] : 'p, ?= [['::y 2] { 1 }]",
+    ]);
+}
+
+#[test]
+fn test_wrong_propagation() {
+    run_wrong_with(&["-e", "() ?- ?^"]);
+    run_wrong_with(&["-e", "1 ?+ ?@ 3"]);
+    run_wrong_with(&["-e", "1 ?+ ?@ { #0 } : ['string]"]);
+}
+
+#[test]
+fn test_wrong_unhandled_variant() {
+    run_wrong("tests/scripts/wrong-unhandled-variant.fuz");
+    run_wrong_with(&["-e", "['a [] 'b []] |+ 't. [] : 't:a ?? #['a {1} 'c {2}]"]);
+    run_wrong_with(&["-e", "['a [] 'b []] |+ 't. [] : 't:a ?? #['a {1}]"]);
+    run_wrong_with(&["-e", "['a [':x 'i32]] |+ 't. [':x 1] : 't"]);
+}
+
+#[test]
+fn test_wrong_generic_function() {
+    run_wrong_with(&["-e", "{ #0 } : ['$T '$T], -> 'f. f @ 1 \"a\""]);
+    run_wrong_with(&["-e", "{ #0 } : ['$T '[$T]], -> 'f. f @ 1 [1 \"a\"]"]);
+    run_wrong_with(&[
+        "-e",
+        "{ #0 } : ['[$T] ['$T]], -> 'f. f @ [1] { #0 } : ['string]",
+    ]);
+    run_wrong_with(&["-e", "[':x '$T] |> 'p"]);
+    run_wrong_with(&["-e", "[':x 'i32] |> '$p"]);
+    run_wrong_with(&["-e", "['area []] |& '$a"]);
+    run_wrong_with(&[
+        "-e",
+        "[':x 'i32] |> 'Point. { #0 } : ['Point], -> 'f. f @ \"s\"",
+    ]);
+}
+
+#[test]
+fn test_wrong_overload() {
+    run_wrong_with(&[
+        "-e",
+        "[':x 'f64] |> 'v. { ## } : ['v], -> 'v:f. { ## } : ['_], -> 'v:f.",
+    ]);
+    run_wrong_with(&[
+        "-e",
+        "[':x 'f64] |> 'v. { ## } : [], -> 'v:f. { ## } : ['f64], -> 'v:f.",
+    ]);
+    run_wrong_with(&[
+        "-e",
+        "[':x 'f64] |> 'v. { ## } : ['v], -> 'v:f. { ## } : ['f64], -> 'v:f. [':x 1.0] : 'v, f 1",
+    ]);
+    run_wrong_with(&["-e", "{ ## } : ['string], -> 'i32:+."]);
+}
+
+#[test]
+fn test_wrong_interface() {
+    run_wrong_with(&["-e", "['area []] |& 'a. 'i32 &> 'a"]);
+    run_wrong_with(&["-e", "['@ ['string]] |& 'a. 'string &> 'a"]);
+    run_wrong_with(&["-e", "['# []] |& 'a. { #0 # } : ['a], -> 'f. f @ \"abc\""]);
+    run_wrong_with(&["-e", "[] |& 'a. [] |& 'a"]);
+    run_wrong_with(&["-e", "[] |& 'a. 'p &> 'a"]);
+}
+
+#[test]
+fn test_wrong_typed_array() {
+    run_code("tests/scripts/wrong-typed-array.fuz", "", 3);
+    run_wrong_with(&["-e", "[':xs '[f64]] |> 's. [':xs [1 2]] : 's"]);
+}
+
 #[test]
 fn test_wrong_popped_type() {
     run_wrong("tests/scripts/wrong-popped-type.fuz");